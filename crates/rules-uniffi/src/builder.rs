@@ -6,12 +6,19 @@ use std::sync::{Arc, RwLock};
 
 use sargon::IndexSet;
 
+use crate::models::MatrixTemplate;
 use crate::prelude::*;
 
 #[derive(Debug, uniffi::Object)]
 pub struct SecurityShieldBuilder {
     wrapped: RwLock<Option<MatrixBuilder>>,
+    history: RwLock<MatrixBuilderHistory>,
     name: RwLock<String>,
+    /// The shield being edited, if this builder was created from an existing
+    /// shield, used to keep its metadata when building.
+    editing: Option<rules::SecurityStructureOfFactorSourceIds>,
+    /// Notified after each mutation, if set.
+    listener: RwLock<Option<SecurityShieldBuilderListenerHolder>>,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, uniffi::Object)]
@@ -20,14 +27,38 @@ pub struct SecurityStructureOfFactorSourceIds {
     pub wrapped: rules::SecurityStructureOfFactorSourceIds,
 }
 
+#[uniffi::export]
+impl SecurityStructureOfFactorSourceIds {
+    /// Imports a shield from `json`, validating its matrix of factors.
+    #[uniffi::constructor]
+    pub fn from_json(json: String) -> Result<Arc<Self>, CommonError> {
+        let wrapped = rules::SecurityStructureOfFactorSourceIds::from_json_str(json)?;
+        Ok(Arc::new(Self { wrapped }))
+    }
+
+    /// Exports this shield as JSON, which can be imported using `from_json`.
+    pub fn to_json(&self) -> String {
+        self.wrapped.to_json_string()
+    }
+}
+
 impl SecurityShieldBuilder {
-    fn get<R>(&self, with_non_consumed_builder: impl Fn(&MatrixBuilder) -> R) -> R {
-        let binding = self.wrapped.read().unwrap();
+    /// The name of a new shield, until changed using `set_name`.
+    const DEFAULT_NAME: &'static str = "My Shield";
+
+    fn get<R>(
+        &self,
+        with_non_consumed_builder: impl Fn(&MatrixBuilder) -> R,
+    ) -> Result<R, CommonError> {
+        let binding = self
+            .wrapped
+            .read()
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)?;
 
         let Some(builder) = binding.as_ref() else {
-            unreachable!("Already built, should not have happened.")
+            return Err(CommonError::AlreadyBuilt);
         };
-        with_non_consumed_builder(builder)
+        Ok(with_non_consumed_builder(builder))
     }
 
     fn with<R, E: Into<CommonError>>(
@@ -44,14 +75,49 @@ impl SecurityShieldBuilder {
         with_non_consumed_builder(builder).map_err(|e| Into::<CommonError>::into(e))
     }
 
-    fn validation_for_addition_of_factor_source_by_calling(
+    /// Performs `mutation` on the builder, recording it in the history so that
+    /// it can be undone.
+    fn mutate(&self, mutation: MatrixBuilderMutation) -> Result<(), CommonError> {
+        let mut history = self
+            .history
+            .write()
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)?;
+        let (changed, result) = self.with(|builder| {
+            let before = builder.clone();
+            let result = history.mutate(builder, mutation);
+            Ok::<_, CommonError>((*builder != before, result))
+        })?;
+        drop(history);
+        if changed {
+            self.notify_listener();
+        }
+        result.map_err(Into::into)
+    }
+
+    /// Passes a snapshot of the builder to the listener, if any and if not
+    /// already built.
+    fn notify_listener(&self) {
+        // The listener is cloned out of the lock, so that `on_change` can set
+        // or remove the listener without deadlocking.
+        let Some(listener) = self
+            .listener
+            .read()
+            .ok()
+            .and_then(|holder| holder.as_ref().map(|holder| holder.0.clone()))
+        else {
+            return;
+        };
+        let Ok(snapshot) = self.snapshot() else {
+            return;
+        };
+        listener.on_change(snapshot);
+    }
+
+    fn validation_for_addition_of_factor_source_by_calling<T, U: From<T>>(
         &self,
         factor_sources: Vec<Arc<FactorSourceID>>,
-        call: impl Fn(
-            &MatrixBuilder,
-            &IndexSet<sargon::FactorSourceID>,
-        ) -> IndexSet<rules::FactorSourceInRoleBuilderValidationStatus>,
-    ) -> Result<Vec<Arc<FactorSourceValidationStatus>>, CommonError> {
+        call: impl Fn(&MatrixBuilder, &IndexSet<sargon::FactorSourceID>) -> IndexSet<T>,
+    ) -> Result<Vec<Arc<U>>, CommonError> {
         let input = &factor_sources
             .clone()
             .into_iter()
@@ -60,11 +126,7 @@ impl SecurityShieldBuilder {
         self.with(|builder| {
             let xs = call(builder, input);
 
-            let xs = xs
-                .into_iter()
-                .map(Into::<FactorSourceValidationStatus>::into)
-                .map(Arc::new)
-                .collect();
+            let xs = xs.into_iter().map(U::from).map(Arc::new).collect();
 
             Ok::<_, CommonError>(xs)
         })
@@ -77,16 +139,77 @@ impl SecurityShieldBuilder {
     pub fn new() -> Arc<Self> {
         Arc::new(Self {
             wrapped: RwLock::new(Some(MatrixBuilder::new())),
-            name: RwLock::new("My Shield".to_owned()),
+            history: RwLock::new(MatrixBuilderHistory::new()),
+            name: RwLock::new(Self::DEFAULT_NAME.to_owned()),
+            editing: None,
+            listener: RwLock::new(None),
+        })
+    }
+
+    /// Creates a builder seeded with the roles, number of days until auto confirm
+    /// and name of an existing shield, building it returns an updated version
+    /// of `shield`, with the same id.
+    #[uniffi::constructor]
+    pub fn with_security_structure(shield: Arc<SecurityStructureOfFactorSourceIds>) -> Arc<Self> {
+        let shield = shield.wrapped.clone();
+        Arc::new(Self {
+            wrapped: RwLock::new(Some(shield.matrix_builder())),
+            history: RwLock::new(MatrixBuilderHistory::new()),
+            name: RwLock::new(shield.metadata.display_name.value.clone()),
+            editing: Some(shield),
+            listener: RwLock::new(None),
         })
     }
+
+    /// Creates a builder seeded with `template` materialized using
+    /// `factor_source_ids`, i.e. each template slot is assigned one of the
+    /// FactorSourceIDs of its kind.
+    #[uniffi::constructor]
+    pub fn with_matrix_template(
+        template: Arc<MatrixTemplate>,
+        factor_source_ids: Vec<Arc<FactorSourceID>>,
+    ) -> Result<Arc<Self>, CommonError> {
+        let matrix = template
+            .wrapped
+            .clone()
+            .materialize(factor_source_ids.iter().map(|f| f.inner))?;
+        Ok(Arc::new(Self {
+            wrapped: RwLock::new(Some(MatrixBuilder::with_matrix(matrix))),
+            history: RwLock::new(MatrixBuilderHistory::new()),
+            name: RwLock::new(Self::DEFAULT_NAME.to_owned()),
+            editing: None,
+            listener: RwLock::new(None),
+        }))
+    }
+
+    /// Returns an independent copy of this builder, with the same roles,
+    /// threshold, number of days until auto confirm and name, e.g. to try
+    /// "what if" changes on a draft. The fork gets a copy of the undo/redo
+    /// history and, if editing an existing shield, builds an updated version
+    /// of that same shield. The listener is not copied.
+    pub fn fork(&self) -> Result<Arc<Self>, CommonError> {
+        // Same lock order as `mutate`, history first, so that the builder and
+        // history copied are consistent with each other.
+        let history = self
+            .history
+            .read()
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)?;
+        let builder = self.get(|builder| builder.clone())?;
+        Ok(Arc::new(Self {
+            wrapped: RwLock::new(Some(builder)),
+            history: RwLock::new(history.clone()),
+            name: RwLock::new(self.get_name()?),
+            editing: self.editing.clone(),
+            listener: RwLock::new(None),
+        }))
+    }
 }
 
 impl SecurityShieldBuilder {
     fn get_factors(
         &self,
         access: impl Fn(&MatrixBuilder) -> &Vec<sargon::FactorSourceID>,
-    ) -> Vec<Arc<FactorSourceID>> {
+    ) -> Result<Vec<Arc<FactorSourceID>>, CommonError> {
         self.get(|builder| {
             let factors = access(builder);
             factors.iter().map(FactorSourceID::new).collect::<Vec<_>>()
@@ -99,31 +222,40 @@ impl SecurityShieldBuilder {
 // ====================
 #[uniffi::export]
 impl SecurityShieldBuilder {
-    pub fn get_primary_threshold(&self) -> u8 {
+    pub fn get_primary_threshold(&self) -> Result<u8, CommonError> {
         self.get(|builder| builder.get_threshold())
     }
 
-    pub fn get_number_of_days_until_auto_confirm(&self) -> u16 {
+    pub fn get_number_of_days_until_auto_confirm(&self) -> Result<u16, CommonError> {
         self.get(|builder| builder.get_number_of_days_until_auto_confirm())
     }
 
-    pub fn get_name(&self) -> String {
-        self.name.read().unwrap().clone()
+    pub fn get_name(&self) -> Result<String, CommonError> {
+        self.name
+            .read()
+            .map(|name| name.clone())
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)
     }
 
-    pub fn get_primary_threshold_factors(&self) -> Vec<Arc<FactorSourceID>> {
+    /// The current state of the builder, the same as passed to the listener.
+    pub fn snapshot(&self) -> Result<SecurityShieldBuilderSnapshot, CommonError> {
+        let name = self.get_name()?;
+        self.get(|builder| SecurityShieldBuilderSnapshot::new(name.clone(), builder))
+    }
+
+    pub fn get_primary_threshold_factors(&self) -> Result<Vec<Arc<FactorSourceID>>, CommonError> {
         self.get_factors(|builder| builder.get_primary_threshold_factors())
     }
 
-    pub fn get_primary_override_factors(&self) -> Vec<Arc<FactorSourceID>> {
+    pub fn get_primary_override_factors(&self) -> Result<Vec<Arc<FactorSourceID>>, CommonError> {
         self.get_factors(|builder| builder.get_primary_override_factors())
     }
 
-    pub fn get_recovery_factors(&self) -> Vec<Arc<FactorSourceID>> {
+    pub fn get_recovery_factors(&self) -> Result<Vec<Arc<FactorSourceID>>, CommonError> {
         self.get_factors(|builder| builder.get_recovery_factors())
     }
 
-    pub fn get_confirmation_factors(&self) -> Vec<Arc<FactorSourceID>> {
+    pub fn get_confirmation_factors(&self) -> Result<Vec<Arc<FactorSourceID>>, CommonError> {
         self.get_factors(|builder| builder.get_confirmation_factors())
     }
 }
@@ -133,8 +265,40 @@ impl SecurityShieldBuilder {
 // ====================
 #[uniffi::export]
 impl SecurityShieldBuilder {
-    pub fn set_name(&self, name: String) {
-        *self.name.write().unwrap() = name
+    pub fn set_name(&self, name: String) -> Result<(), CommonError> {
+        let mut current = self
+            .name
+            .write()
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)?;
+        if *current == name {
+            return Ok(());
+        }
+        *current = name;
+        drop(current);
+        self.notify_listener();
+        Ok(())
+    }
+
+    /// Sets `listener` to be notified after each mutation, replacing any
+    /// previously set listener.
+    pub fn set_listener(
+        &self,
+        listener: Box<dyn SecurityShieldBuilderListener>,
+    ) -> Result<(), CommonError> {
+        *self
+            .listener
+            .write()
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)? =
+            Some(SecurityShieldBuilderListenerHolder(Arc::from(listener)));
+        Ok(())
+    }
+
+    pub fn remove_listener(&self) -> Result<(), CommonError> {
+        *self
+            .listener
+            .write()
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)? = None;
+        Ok(())
     }
 
     /// Adds the factor source to the primary role threshold list.
@@ -142,45 +306,98 @@ impl SecurityShieldBuilder {
         &self,
         factor_source_id: Arc<FactorSourceID>,
     ) -> Result<(), CommonError> {
-        self.with(|builder| builder.add_factor_source_to_primary_threshold(factor_source_id.inner))
+        self.mutate(MatrixBuilderMutation::AddFactorSourceToPrimaryThreshold(
+            factor_source_id.inner,
+        ))
     }
 
     pub fn add_factor_source_to_primary_override(
         &self,
         factor_source_id: Arc<FactorSourceID>,
     ) -> Result<(), CommonError> {
-        self.with(|builder| builder.add_factor_source_to_primary_override(factor_source_id.inner))
+        self.mutate(MatrixBuilderMutation::AddFactorSourceToPrimaryOverride(
+            factor_source_id.inner,
+        ))
     }
 
     pub fn remove_factor(&self, factor_source_id: Arc<FactorSourceID>) -> Result<(), CommonError> {
-        self.with(|builder| builder.remove_factor(&factor_source_id.inner))
+        self.mutate(MatrixBuilderMutation::RemoveFactor(factor_source_id.inner))
     }
 
     pub fn set_threshold(&self, threshold: u8) -> Result<(), CommonError> {
-        self.with(|builder| builder.set_threshold(threshold))
+        self.mutate(MatrixBuilderMutation::SetThreshold(threshold))
     }
 
     pub fn set_number_of_days_until_auto_confirm(
         &self,
         number_of_days: u16,
     ) -> Result<(), CommonError> {
-        self.with(|builder| builder.set_number_of_days_until_auto_confirm(number_of_days))
+        self.mutate(MatrixBuilderMutation::SetNumberOfDaysUntilAutoConfirm(
+            number_of_days,
+        ))
     }
 
     pub fn add_factor_source_to_recovery_override(
         &self,
         factor_source_id: Arc<FactorSourceID>,
     ) -> Result<(), CommonError> {
-        self.with(|builder| builder.add_factor_source_to_recovery_override(factor_source_id.inner))
+        self.mutate(MatrixBuilderMutation::AddFactorSourceToRecoveryOverride(
+            factor_source_id.inner,
+        ))
     }
 
     pub fn add_factor_source_to_confirmation_override(
         &self,
         factor_source_id: Arc<FactorSourceID>,
     ) -> Result<(), CommonError> {
-        self.with(|builder| {
-            builder.add_factor_source_to_confirmation_override(factor_source_id.inner)
-        })
+        self.mutate(
+            MatrixBuilderMutation::AddFactorSourceToConfirmationOverride(factor_source_id.inner),
+        )
+    }
+
+    /// Reverts the last mutation, including its side effects, e.g. a lowered
+    /// threshold when removing a factor. Returns `false` if there was nothing
+    /// to undo.
+    pub fn undo(&self) -> Result<bool, CommonError> {
+        let mut history = self
+            .history
+            .write()
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)?;
+        let changed = self.with(|builder| Ok::<_, CommonError>(history.undo(builder).is_some()))?;
+        drop(history);
+        if changed {
+            self.notify_listener();
+        }
+        Ok(changed)
+    }
+
+    /// Reapplies the last undone mutation. Returns `false` if there was nothing
+    /// to redo.
+    pub fn redo(&self) -> Result<bool, CommonError> {
+        let mut history = self
+            .history
+            .write()
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)?;
+        let changed = self.with(|builder| Ok::<_, CommonError>(history.redo(builder).is_some()))?;
+        drop(history);
+        if changed {
+            self.notify_listener();
+        }
+        Ok(changed)
+    }
+
+    pub fn can_undo(&self) -> Result<bool, CommonError> {
+        self.history
+            .read()
+            .map(|history| history.can_undo())
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)
+    }
+
+    pub fn can_redo(&self) -> Result<bool, CommonError> {
+        self.history
+            .read()
+            .map(|history| history.can_redo())
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)
     }
 
     pub fn validation_for_addition_of_factor_source_of_kind_to_confirmation_override(
@@ -279,21 +496,89 @@ impl SecurityShieldBuilder {
         )
     }
 
+    pub fn validation_in_combination_for_addition_of_factor_source_to_primary_threshold_for_each(
+        &self,
+        factor_sources: Vec<Arc<FactorSourceID>>,
+    ) -> Result<Vec<Arc<FactorSourceInMatrixValidationStatus>>, CommonError> {
+        self.validation_for_addition_of_factor_source_by_calling(
+            factor_sources,
+            |builder, input| {
+                builder.validation_in_combination_for_addition_of_factor_source_to_primary_threshold_for_each(input)
+            },
+        )
+    }
+
+    pub fn validation_in_combination_for_addition_of_factor_source_to_primary_override_for_each(
+        &self,
+        factor_sources: Vec<Arc<FactorSourceID>>,
+    ) -> Result<Vec<Arc<FactorSourceInMatrixValidationStatus>>, CommonError> {
+        self.validation_for_addition_of_factor_source_by_calling(
+            factor_sources,
+            |builder, input| {
+                builder.validation_in_combination_for_addition_of_factor_source_to_primary_override_for_each(input)
+            },
+        )
+    }
+
+    pub fn validation_in_combination_for_addition_of_factor_source_to_recovery_override_for_each(
+        &self,
+        factor_sources: Vec<Arc<FactorSourceID>>,
+    ) -> Result<Vec<Arc<FactorSourceInMatrixValidationStatus>>, CommonError> {
+        self.validation_for_addition_of_factor_source_by_calling(
+            factor_sources,
+            |builder, input| {
+                builder.validation_in_combination_for_addition_of_factor_source_to_recovery_override_for_each(input)
+            },
+        )
+    }
+
+    pub fn validation_in_combination_for_addition_of_factor_source_to_confirmation_override_for_each(
+        &self,
+        factor_sources: Vec<Arc<FactorSourceID>>,
+    ) -> Result<Vec<Arc<FactorSourceInMatrixValidationStatus>>, CommonError> {
+        self.validation_for_addition_of_factor_source_by_calling(
+            factor_sources,
+            |builder, input| {
+                builder.validation_in_combination_for_addition_of_factor_source_to_confirmation_override_for_each(input)
+            },
+        )
+    }
+
+    /// Builds the shield the builder would build, without consuming the builder,
+    /// e.g. to show a summary before the user confirms. Unless editing an
+    /// existing shield, every preview gets a new id.
+    pub fn preview_build(&self) -> Result<SecurityStructureOfFactorSourceIds, CommonError> {
+        let builder = self.get(|builder| builder.clone())?;
+        self.shield_from(builder)
+    }
+
     pub fn build(self: Arc<Self>) -> Result<SecurityStructureOfFactorSourceIds, CommonError> {
-        let mut binding = self
-            .wrapped
+        // Build from a clone so that a failed build leaves the builder intact.
+        let builder = self.get(|builder| builder.clone())?;
+        let shield = self.shield_from(builder)?;
+        self.wrapped
             .write()
-            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)?;
-        let builder = binding.take().ok_or(CommonError::AlreadyBuilt)?;
-        let wrapped_matrix = builder
-            .build()
-            .map_err(|e| CommonError::BuildError(format!("{:?}", e)))?;
+            .map_err(|_| CommonError::MatrixBuilderRwLockPoisoned)?
+            .take()
+            .ok_or(CommonError::AlreadyBuilt)?;
+        Ok(shield)
+    }
+}
 
-        let name = self.get_name();
+impl SecurityShieldBuilder {
+    fn shield_from(
+        &self,
+        builder: MatrixBuilder,
+    ) -> Result<SecurityStructureOfFactorSourceIds, CommonError> {
+        let wrapped_matrix = builder.build()?;
+
+        let name = self.get_name()?;
         let display_name =
             sargon::DisplayName::new(name).map_err(|e| CommonError::Sargon(format!("{:?}", e)))?;
-        let wrapped_shield =
-            rules::SecurityStructureOfFactorSourceIds::new(display_name, wrapped_matrix);
+        let wrapped_shield = match self.editing.as_ref() {
+            Some(shield) => shield.edited(display_name, wrapped_matrix),
+            None => rules::SecurityStructureOfFactorSourceIds::new(display_name, wrapped_matrix),
+        };
 
         let shield = SecurityStructureOfFactorSourceIds {
             wrapped: wrapped_shield,
@@ -314,12 +599,15 @@ mod tests {
     fn test() {
         let sut = SUT::new();
 
-        assert_eq!(sut.get_name(), "My Shield");
-        sut.set_name("S.H.I.E.L.D.".to_owned());
+        assert_eq!(sut.get_name().unwrap(), "My Shield");
+        sut.set_name("S.H.I.E.L.D.".to_owned()).unwrap();
 
-        assert_eq!(sut.get_number_of_days_until_auto_confirm(), 14);
+        assert_eq!(sut.get_number_of_days_until_auto_confirm().unwrap(), 14);
         sut.set_number_of_days_until_auto_confirm(u16::MAX).unwrap();
-        assert_eq!(sut.get_number_of_days_until_auto_confirm(), u16::MAX);
+        assert_eq!(
+            sut.get_number_of_days_until_auto_confirm().unwrap(),
+            u16::MAX
+        );
 
         // Primary
         let sim_prim =
@@ -345,18 +633,18 @@ mod tests {
         sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
             .unwrap();
         assert_eq!(
-            sut.get_primary_threshold_factors(),
+            sut.get_primary_threshold_factors().unwrap(),
             vec![FactorSourceID::sample_device()]
         );
         _ = sut.set_threshold(1);
-        assert_eq!(sut.get_primary_threshold(), 1);
+        assert_eq!(sut.get_primary_threshold().unwrap(), 1);
         sut.add_factor_source_to_primary_override(FactorSourceID::sample_arculus())
             .unwrap();
         sut.add_factor_source_to_primary_override(FactorSourceID::sample_arculus_other())
             .unwrap();
 
         assert_eq!(
-            sut.get_primary_override_factors(),
+            sut.get_primary_override_factors().unwrap(),
             vec![
                 FactorSourceID::sample_arculus(),
                 FactorSourceID::sample_arculus_other()
@@ -380,7 +668,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(
-            sut.get_recovery_factors(),
+            sut.get_recovery_factors().unwrap(),
             vec![
                 FactorSourceID::sample_ledger(),
                 FactorSourceID::sample_ledger_other()
@@ -402,7 +690,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(
-            sut.get_confirmation_factors(),
+            sut.get_confirmation_factors().unwrap(),
             vec![FactorSourceID::sample_device(),]
         );
 
@@ -494,4 +782,445 @@ mod tests {
             &vec![FactorSourceID::sample_device().inner]
         );
     }
+
+    #[test]
+    fn edit_existing_shield() {
+        let original = rules::SecurityStructureOfFactorSourceIds::sample();
+        let sut = SUT::with_security_structure(Arc::new(SecurityStructureOfFactorSourceIds {
+            wrapped: original.clone(),
+        }));
+        assert_eq!(
+            sut.get_name().unwrap(),
+            original.metadata.display_name.value
+        );
+        assert_eq!(sut.get_number_of_days_until_auto_confirm().unwrap(), 14);
+        assert_eq!(sut.get_primary_threshold().unwrap(), 2);
+
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_arculus())
+            .unwrap();
+        let shield = sut.build().unwrap();
+
+        assert_eq!(shield.wrapped.metadata.id, original.metadata.id);
+        assert_eq!(
+            shield.wrapped.metadata.created_on,
+            original.metadata.created_on
+        );
+        assert!(shield.wrapped.metadata.last_updated_on > original.metadata.last_updated_on);
+        assert_eq!(
+            shield
+                .wrapped
+                .matrix_of_factors
+                .confirmation()
+                .get_override_factors(),
+            &vec![
+                sargon::FactorSourceID::sample_password(),
+                FactorSourceID::sample_arculus().inner
+            ]
+        );
+    }
+
+    #[test]
+    fn undo_redo() {
+        let sut = SUT::new();
+        assert!(!sut.can_undo().unwrap());
+        assert!(!sut.undo().unwrap());
+
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_ledger())
+            .unwrap();
+        sut.set_threshold(2).unwrap();
+
+        sut.remove_factor(FactorSourceID::sample_ledger()).unwrap();
+        assert_eq!(sut.get_primary_threshold().unwrap(), 1);
+
+        assert!(sut.undo().unwrap());
+        assert_eq!(sut.get_primary_threshold().unwrap(), 2);
+        assert_eq!(
+            sut.get_primary_threshold_factors().unwrap(),
+            vec![
+                FactorSourceID::sample_device(),
+                FactorSourceID::sample_ledger()
+            ]
+        );
+
+        assert!(sut.can_redo().unwrap());
+        assert!(sut.redo().unwrap());
+        assert_eq!(sut.get_primary_threshold().unwrap(), 1);
+        assert!(!sut.can_redo().unwrap());
+        assert!(!sut.redo().unwrap());
+    }
+
+    #[test]
+    fn validation_in_combination() {
+        let sut = SUT::new();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_ledger())
+            .unwrap();
+
+        let xs = sut
+            .validation_in_combination_for_addition_of_factor_source_to_recovery_override_for_each(
+                vec![
+                    FactorSourceID::sample_ledger(),
+                    FactorSourceID::sample_arculus(),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            xs.into_iter()
+                .map(|x| x.validation.is_ok())
+                .collect::<Vec<_>>(),
+            vec![false, true]
+        );
+    }
+
+    #[test]
+    fn structured_errors() {
+        use crate::models::{
+            ForeverInvalidReason, MatrixBuilderValidation, MatrixRolesInCombinationForeverInvalid,
+            MatrixRolesInCombinationViolation, RoleBuilderValidation,
+        };
+
+        let sut = SUT::new();
+        assert_eq!(
+            sut.add_factor_source_to_primary_override(FactorSourceID::sample_password()),
+            Err(CommonError::InvalidMatrix {
+                violation: MatrixBuilderValidation::RoleInIsolation {
+                    role: RoleKind::Primary,
+                    violation: RoleBuilderValidation::ForeverInvalid {
+                        reason: ForeverInvalidReason::PrimaryCannotHavePasswordInOverrideList
+                    }
+                }
+            })
+        );
+
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.set_threshold(1).unwrap();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_ledger())
+            .unwrap();
+        assert_eq!(
+            sut.build(),
+            Err(CommonError::InvalidMatrix {
+                violation: MatrixBuilderValidation::CombinationViolation {
+                    violation: MatrixRolesInCombinationViolation::ForeverInvalid {
+                        violation:
+                            MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn preview_build_does_not_consume() {
+        let sut = SUT::new();
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.set_threshold(1).unwrap();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_arculus())
+            .unwrap();
+
+        let preview = sut.preview_build().unwrap();
+        assert_eq!(sut.get_primary_threshold().unwrap(), 1);
+        sut.set_name("Preview".to_owned()).unwrap();
+
+        let shield = sut.clone().build().unwrap();
+        assert_eq!(
+            shield.wrapped.matrix_of_factors,
+            preview.wrapped.matrix_of_factors
+        );
+        assert_eq!(shield.wrapped.metadata.display_name.value, "Preview");
+    }
+
+    #[test]
+    fn getters_after_build_return_already_built() {
+        let sut = SUT::new();
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.set_threshold(1).unwrap();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_arculus())
+            .unwrap();
+        sut.clone().build().unwrap();
+
+        assert_eq!(sut.get_primary_threshold(), Err(CommonError::AlreadyBuilt));
+        assert_eq!(
+            sut.get_number_of_days_until_auto_confirm(),
+            Err(CommonError::AlreadyBuilt)
+        );
+        assert_eq!(sut.get_recovery_factors(), Err(CommonError::AlreadyBuilt));
+        assert_eq!(sut.preview_build(), Err(CommonError::AlreadyBuilt));
+        assert_eq!(sut.clone().build(), Err(CommonError::AlreadyBuilt));
+    }
+
+    #[test]
+    fn failed_build_does_not_consume() {
+        let sut = SUT::new();
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.set_threshold(1).unwrap();
+
+        assert!(matches!(
+            sut.clone().build(),
+            Err(CommonError::InvalidMatrix { .. })
+        ));
+
+        assert_eq!(sut.get_primary_threshold().unwrap(), 1);
+        assert_eq!(
+            sut.get_primary_threshold_factors().unwrap(),
+            vec![FactorSourceID::sample_device()]
+        );
+        assert_eq!(sut.get_name().unwrap(), "My Shield");
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_arculus())
+            .unwrap();
+        assert!(sut.clone().build().is_ok());
+    }
+
+    #[test]
+    fn fork_is_independent() {
+        let sut = SUT::new();
+        sut.set_name("Draft".to_owned()).unwrap();
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.set_threshold(1).unwrap();
+        sut.set_number_of_days_until_auto_confirm(42).unwrap();
+
+        let fork = sut.fork().unwrap();
+        assert_eq!(fork.get_name().unwrap(), "Draft");
+        assert_eq!(fork.get_primary_threshold().unwrap(), 1);
+        assert_eq!(fork.get_number_of_days_until_auto_confirm().unwrap(), 42);
+        assert_eq!(
+            fork.get_primary_threshold_factors().unwrap(),
+            vec![FactorSourceID::sample_device()]
+        );
+        assert!(fork.can_undo().unwrap());
+
+        fork.set_name("What if".to_owned()).unwrap();
+        fork.add_factor_source_to_primary_threshold(FactorSourceID::sample_ledger())
+            .unwrap();
+        fork.set_threshold(2).unwrap();
+
+        assert_eq!(sut.get_name().unwrap(), "Draft");
+        assert_eq!(sut.get_primary_threshold().unwrap(), 1);
+        assert_eq!(
+            sut.get_primary_threshold_factors().unwrap(),
+            vec![FactorSourceID::sample_device()]
+        );
+
+        assert!(fork.undo().unwrap());
+        assert_eq!(fork.get_primary_threshold().unwrap(), 1);
+        assert!(!sut.can_redo().unwrap());
+    }
+
+    #[test]
+    fn fork_after_build_fails() {
+        let sut = SUT::new();
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.set_threshold(1).unwrap();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_arculus())
+            .unwrap();
+        sut.clone().build().unwrap();
+        assert_eq!(sut.fork().err(), Some(CommonError::AlreadyBuilt));
+    }
+
+    #[derive(Default)]
+    struct RecordingListener {
+        snapshots: Arc<std::sync::Mutex<Vec<SecurityShieldBuilderSnapshot>>>,
+    }
+
+    impl SecurityShieldBuilderListener for RecordingListener {
+        fn on_change(&self, snapshot: SecurityShieldBuilderSnapshot) {
+            self.snapshots.lock().unwrap().push(snapshot);
+        }
+    }
+
+    #[test]
+    fn listener_is_notified_after_each_mutation() {
+        let sut = SUT::new();
+        let listener = RecordingListener::default();
+        let snapshots = listener.snapshots.clone();
+        sut.set_listener(Box::new(listener)).unwrap();
+
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        let last = snapshots.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(
+            last.primary_threshold_factors,
+            vec![FactorSourceID::sample_device()]
+        );
+        assert_eq!(last.primary_threshold, 0);
+        assert!(!last.violations.is_empty());
+
+        sut.set_threshold(1).unwrap();
+        sut.set_name("Observed".to_owned()).unwrap();
+        sut.undo().unwrap();
+        sut.redo().unwrap();
+
+        let snapshots = snapshots.lock().unwrap().clone();
+        assert_eq!(snapshots.len(), 5);
+        assert_eq!(snapshots[1].primary_threshold, 1);
+        assert_eq!(snapshots[2].name, "Observed");
+        assert_eq!(snapshots[3].primary_threshold, 0);
+        assert_eq!(snapshots[4].primary_threshold, 1);
+        assert_eq!(snapshots[4], sut.snapshot().unwrap());
+    }
+
+    #[test]
+    fn listener_is_not_notified_on_failed_mutation_and_can_be_removed() {
+        let sut = SUT::new();
+        let listener = RecordingListener::default();
+        let snapshots = listener.snapshots.clone();
+        sut.set_listener(Box::new(listener)).unwrap();
+
+        assert!(sut
+            .add_factor_source_to_primary_override(FactorSourceID::sample_password())
+            .is_err());
+        assert!(snapshots.lock().unwrap().is_empty());
+
+        sut.set_name(sut.get_name().unwrap()).unwrap();
+        assert!(snapshots.lock().unwrap().is_empty());
+
+        sut.set_number_of_days_until_auto_confirm(42).unwrap();
+        assert_eq!(snapshots.lock().unwrap().len(), 1);
+
+        sut.remove_listener().unwrap();
+        sut.set_number_of_days_until_auto_confirm(7).unwrap();
+        assert_eq!(snapshots.lock().unwrap().len(), 1);
+    }
+
+    struct RemovingListener {
+        builder: std::sync::Weak<SUT>,
+        calls: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl SecurityShieldBuilderListener for RemovingListener {
+        fn on_change(&self, _snapshot: SecurityShieldBuilderSnapshot) {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if let Some(builder) = self.builder.upgrade() {
+                builder.remove_listener().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn listener_can_remove_itself_when_notified() {
+        let sut = SUT::new();
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        sut.set_listener(Box::new(RemovingListener {
+            builder: Arc::downgrade(&sut),
+            calls: calls.clone(),
+        }))
+        .unwrap();
+
+        sut.set_name("Removed".to_owned()).unwrap();
+        sut.set_name("Not observed".to_owned()).unwrap();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn shield_json_roundtrip() {
+        let shield = SecurityStructureOfFactorSourceIds {
+            wrapped: rules::SecurityStructureOfFactorSourceIds::sample(),
+        };
+        let imported = SecurityStructureOfFactorSourceIds::from_json(shield.to_json()).unwrap();
+        assert_eq!(*imported, shield);
+    }
+
+    #[test]
+    fn shield_from_invalid_json() {
+        assert!(matches!(
+            SecurityStructureOfFactorSourceIds::from_json("{}".to_owned()),
+            Err(CommonError::InvalidJson { .. })
+        ));
+    }
+
+    #[test]
+    fn shield_from_json_with_invalid_matrix() {
+        use crate::models::{MatrixBuilderValidation, NotYetValidReason, RoleBuilderValidation};
+
+        // Leaves the confirmation role without factors.
+        let mut json =
+            serde_json::to_value(rules::SecurityStructureOfFactorSourceIds::sample()).unwrap();
+        json["matrixOfFactors"]["confirmationRole"]["overrideFactors"] = serde_json::json!([]);
+        assert_eq!(
+            SecurityStructureOfFactorSourceIds::from_json(json.to_string()).err(),
+            Some(CommonError::InvalidMatrix {
+                violation: MatrixBuilderValidation::RoleInIsolation {
+                    role: RoleKind::Confirmation,
+                    violation: RoleBuilderValidation::NotYetValid {
+                        reason: NotYetValidReason::RoleMustHaveAtLeastOneFactor
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn with_matrix_template() {
+        let template = matrix_template_presets()
+            .into_iter()
+            .map(|named| named.get_template())
+            .find(|t| t.wrapped == rules::MatrixTemplate::config_11())
+            .unwrap();
+        let sut = SUT::with_matrix_template(
+            template,
+            vec![
+                FactorSourceID::sample_device(),
+                FactorSourceID::sample_ledger(),
+                FactorSourceID::sample_password(),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(sut.get_name().unwrap(), SUT::new().get_name().unwrap());
+        assert_eq!(
+            sut.get_primary_threshold_factors().unwrap(),
+            vec![
+                FactorSourceID::sample_device(),
+                FactorSourceID::sample_ledger()
+            ]
+        );
+        assert_eq!(
+            sut.get_confirmation_factors().unwrap(),
+            vec![FactorSourceID::sample_password()]
+        );
+        assert!(sut.snapshot().unwrap().violations.is_empty());
+    }
+
+    #[test]
+    fn with_matrix_template_missing_factor_source() {
+        let template = Arc::new(MatrixTemplate {
+            wrapped: rules::MatrixTemplate::config_11(),
+        });
+        assert_eq!(
+            SUT::with_matrix_template(
+                template,
+                vec![
+                    FactorSourceID::sample_device(),
+                    FactorSourceID::sample_ledger(),
+                ],
+            )
+            .err(),
+            Some(CommonError::TemplateMaterialization {
+                template: crate::models::FactorSourceTemplate {
+                    kind: FactorSourceKind::Password,
+                    id: 0
+                },
+                role: RoleKind::Confirmation,
+                supplied: 0,
+                needed: 1,
+            })
+        );
+    }
 }
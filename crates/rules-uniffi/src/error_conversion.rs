@@ -11,18 +11,62 @@ pub enum CommonError {
     #[error("Matrix builder RwLock poisoned")]
     MatrixBuilderRwLockPoisoned,
 
-    #[error("Build error {0}")]
-    BuildError(String),
+    #[error("Invalid matrix {violation:?}")]
+    InvalidMatrix {
+        violation: crate::models::MatrixBuilderValidation,
+    },
+
+    #[error("Invalid role {violation:?}")]
+    InvalidRole {
+        violation: crate::models::RoleBuilderValidation,
+    },
+
+    #[error("Invalid JSON {reason}")]
+    InvalidJson { reason: String },
+
+    #[error("Unable to assign a factor source to template slot {template:?} in role {role:?}, supplied {supplied} factor source(s) of that kind but {needed} needed")]
+    TemplateMaterialization {
+        template: crate::models::FactorSourceTemplate,
+        role: RoleKind,
+        supplied: u32,
+        needed: u32,
+    },
+}
+
+impl From<rules::MatrixBuilderValidation> for CommonError {
+    fn from(val: rules::MatrixBuilderValidation) -> Self {
+        CommonError::InvalidMatrix {
+            violation: val.into(),
+        }
+    }
+}
+
+impl From<rules::RoleBuilderValidation> for CommonError {
+    fn from(val: rules::RoleBuilderValidation) -> Self {
+        CommonError::InvalidRole {
+            violation: val.into(),
+        }
+    }
 }
 
-impl From<MatrixBuilderValidation> for CommonError {
-    fn from(val: MatrixBuilderValidation) -> Self {
-        CommonError::BuildError(format!("{:?}", val))
+impl From<rules::MatrixDeserializationError> for CommonError {
+    fn from(val: rules::MatrixDeserializationError) -> Self {
+        match val {
+            rules::MatrixDeserializationError::InvalidJson { reason, .. } => {
+                CommonError::InvalidJson { reason }
+            }
+            rules::MatrixDeserializationError::InvalidMatrix(violation) => violation.into(),
+        }
     }
 }
 
-impl From<RoleBuilderValidation> for CommonError {
-    fn from(val: RoleBuilderValidation) -> Self {
-        CommonError::BuildError(format!("{:?}", val))
+impl From<rules::MatrixTemplateMaterializationError> for CommonError {
+    fn from(val: rules::MatrixTemplateMaterializationError) -> Self {
+        CommonError::TemplateMaterialization {
+            template: val.template.into(),
+            role: val.role.into(),
+            supplied: val.supplied as u32,
+            needed: val.needed as u32,
+        }
     }
 }
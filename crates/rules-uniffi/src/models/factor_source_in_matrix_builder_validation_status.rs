@@ -0,0 +1,18 @@
+#[derive(Debug, Clone, PartialEq, Eq, Hash, uniffi::Object)]
+pub struct FactorSourceInMatrixValidationStatus {
+    pub role: sargon::RoleKind,
+    pub factor_source_id: sargon::FactorSourceID,
+    pub validation: rules::MatrixBuilderMutateResult,
+}
+
+impl From<rules::FactorSourceInMatrixBuilderValidationStatus>
+    for FactorSourceInMatrixValidationStatus
+{
+    fn from(val: rules::FactorSourceInMatrixBuilderValidationStatus) -> Self {
+        FactorSourceInMatrixValidationStatus {
+            role: val.role,
+            factor_source_id: val.factor_source_id,
+            validation: val.validation,
+        }
+    }
+}
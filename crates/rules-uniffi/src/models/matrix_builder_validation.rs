@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum BasicViolation {
+    /// e.g. tried to remove a factor source which was not found.
+    FactorSourceNotFound,
+    RecoveryCannotSetThreshold,
+    ConfirmationCannotSetThreshold,
+}
+
+impl From<rules::BasicViolation> for BasicViolation {
+    fn from(value: rules::BasicViolation) -> Self {
+        match value {
+            rules::BasicViolation::FactorSourceNotFound => Self::FactorSourceNotFound,
+            rules::BasicViolation::RecoveryCannotSetThreshold => Self::RecoveryCannotSetThreshold,
+            rules::BasicViolation::ConfirmationCannotSetThreshold => {
+                Self::ConfirmationCannotSetThreshold
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum NotYetValidReason {
+    RoleMustHaveAtLeastOneFactor,
+    PrimaryRoleWithPasswordInThresholdListMustHaveAnotherFactor,
+    PrimaryRoleWithThresholdCannotBeZeroWithFactors,
+    PrimaryRoleWithPasswordInThresholdListMustThresholdGreaterThanOne,
+    ThresholdHigherThanThresholdFactorsLen,
+}
+
+impl From<rules::NotYetValidReason> for NotYetValidReason {
+    fn from(value: rules::NotYetValidReason) -> Self {
+        use rules::NotYetValidReason as R;
+        match value {
+            R::RoleMustHaveAtLeastOneFactor => Self::RoleMustHaveAtLeastOneFactor,
+            R::PrimaryRoleWithPasswordInThresholdListMustHaveAnotherFactor => {
+                Self::PrimaryRoleWithPasswordInThresholdListMustHaveAnotherFactor
+            }
+            R::PrimaryRoleWithThresholdCannotBeZeroWithFactors => {
+                Self::PrimaryRoleWithThresholdCannotBeZeroWithFactors
+            }
+            R::PrimaryRoleWithPasswordInThresholdListMustThresholdGreaterThanOne => {
+                Self::PrimaryRoleWithPasswordInThresholdListMustThresholdGreaterThanOne
+            }
+            R::ThresholdHigherThanThresholdFactorsLen => {
+                Self::ThresholdHigherThanThresholdFactorsLen
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum ForeverInvalidReason {
+    FactorSourceAlreadyPresent,
+    PrimaryCannotHaveMultipleDevices,
+    PrimaryCannotHavePasswordInOverrideList,
+    PrimaryCannotContainSecurityQuestions,
+    PrimaryCannotContainTrustedContact,
+    RecoveryRoleThresholdFactorsNotSupported,
+    RecoveryRoleSecurityQuestionsNotSupported,
+    RecoveryRolePasswordNotSupported,
+    ConfirmationRoleThresholdFactorsNotSupported,
+    ConfirmationRoleTrustedContactNotSupported,
+}
+
+impl From<rules::ForeverInvalidReason> for ForeverInvalidReason {
+    fn from(value: rules::ForeverInvalidReason) -> Self {
+        use rules::ForeverInvalidReason as R;
+        match value {
+            R::FactorSourceAlreadyPresent => Self::FactorSourceAlreadyPresent,
+            R::PrimaryCannotHaveMultipleDevices => Self::PrimaryCannotHaveMultipleDevices,
+            R::PrimaryCannotHavePasswordInOverrideList => {
+                Self::PrimaryCannotHavePasswordInOverrideList
+            }
+            R::PrimaryCannotContainSecurityQuestions => Self::PrimaryCannotContainSecurityQuestions,
+            R::PrimaryCannotContainTrustedContact => Self::PrimaryCannotContainTrustedContact,
+            R::RecoveryRoleThresholdFactorsNotSupported => {
+                Self::RecoveryRoleThresholdFactorsNotSupported
+            }
+            R::RecoveryRoleSecurityQuestionsNotSupported => {
+                Self::RecoveryRoleSecurityQuestionsNotSupported
+            }
+            R::RecoveryRolePasswordNotSupported => Self::RecoveryRolePasswordNotSupported,
+            R::ConfirmationRoleThresholdFactorsNotSupported => {
+                Self::ConfirmationRoleThresholdFactorsNotSupported
+            }
+            R::ConfirmationRoleTrustedContactNotSupported => {
+                Self::ConfirmationRoleTrustedContactNotSupported
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum RoleBuilderValidation {
+    BasicViolation { violation: BasicViolation },
+    ForeverInvalid { reason: ForeverInvalidReason },
+    NotYetValid { reason: NotYetValidReason },
+}
+
+impl From<rules::RoleBuilderValidation> for RoleBuilderValidation {
+    fn from(value: rules::RoleBuilderValidation) -> Self {
+        match value {
+            rules::RoleBuilderValidation::BasicViolation(violation) => Self::BasicViolation {
+                violation: violation.into(),
+            },
+            rules::RoleBuilderValidation::ForeverInvalid(reason) => Self::ForeverInvalid {
+                reason: reason.into(),
+            },
+            rules::RoleBuilderValidation::NotYetValid(reason) => Self::NotYetValid {
+                reason: reason.into(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum MatrixRolesInCombinationBasicViolation {
+    FactorSourceNotFoundInAnyRole,
+    NumberOfDaysUntilAutoConfirmMustBeGreaterThanZero,
+}
+
+impl From<rules::MatrixRolesInCombinationBasicViolation>
+    for MatrixRolesInCombinationBasicViolation
+{
+    fn from(value: rules::MatrixRolesInCombinationBasicViolation) -> Self {
+        use rules::MatrixRolesInCombinationBasicViolation as R;
+        match value {
+            R::FactorSourceNotFoundInAnyRole => Self::FactorSourceNotFoundInAnyRole,
+            R::NumberOfDaysUntilAutoConfirmMustBeGreaterThanZero => {
+                Self::NumberOfDaysUntilAutoConfirmMustBeGreaterThanZero
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum MatrixRolesInCombinationForeverInvalid {
+    RecoveryAndConfirmationFactorsOverlap,
+}
+
+impl From<rules::MatrixRolesInCombinationForeverInvalid>
+    for MatrixRolesInCombinationForeverInvalid
+{
+    fn from(value: rules::MatrixRolesInCombinationForeverInvalid) -> Self {
+        match value {
+            rules::MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap => {
+                Self::RecoveryAndConfirmationFactorsOverlap
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum MatrixRolesInCombinationNotYetValid {
+    SingleFactorUsedInPrimaryMustNotBeUsedInAnyOtherRole,
+}
+
+impl From<rules::MatrixRolesInCombinationNotYetValid> for MatrixRolesInCombinationNotYetValid {
+    fn from(value: rules::MatrixRolesInCombinationNotYetValid) -> Self {
+        match value {
+            rules::MatrixRolesInCombinationNotYetValid::SingleFactorUsedInPrimaryMustNotBeUsedInAnyOtherRole => {
+                Self::SingleFactorUsedInPrimaryMustNotBeUsedInAnyOtherRole
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum MatrixRolesInCombinationViolation {
+    Basic {
+        violation: MatrixRolesInCombinationBasicViolation,
+    },
+    ForeverInvalid {
+        violation: MatrixRolesInCombinationForeverInvalid,
+    },
+    NotYetValid {
+        violation: MatrixRolesInCombinationNotYetValid,
+    },
+}
+
+impl From<rules::MatrixRolesInCombinationViolation> for MatrixRolesInCombinationViolation {
+    fn from(value: rules::MatrixRolesInCombinationViolation) -> Self {
+        use rules::MatrixRolesInCombinationViolation as R;
+        match value {
+            R::Basic(violation) => Self::Basic {
+                violation: violation.into(),
+            },
+            R::ForeverInvalid(violation) => Self::ForeverInvalid {
+                violation: violation.into(),
+            },
+            R::NotYetValid(violation) => Self::NotYetValid {
+                violation: violation.into(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum MatrixBuilderValidation {
+    RoleInIsolation {
+        role: RoleKind,
+        violation: RoleBuilderValidation,
+    },
+    CombinationViolation {
+        violation: MatrixRolesInCombinationViolation,
+    },
+}
+
+impl From<rules::MatrixBuilderValidation> for MatrixBuilderValidation {
+    fn from(value: rules::MatrixBuilderValidation) -> Self {
+        match value {
+            rules::MatrixBuilderValidation::RoleInIsolation { role, violation } => {
+                Self::RoleInIsolation {
+                    role: role.into(),
+                    violation: violation.into(),
+                }
+            }
+            rules::MatrixBuilderValidation::CombinationViolation(violation) => {
+                Self::CombinationViolation {
+                    violation: violation.into(),
+                }
+            }
+        }
+    }
+}
+
+/// A single violation of a `MatrixBuilder`, tagged with the roles it concerns
+/// and the FactorSourceIDs causing it.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct MatrixBuilderViolation {
+    pub roles: Vec<RoleKind>,
+    pub factor_source_ids: Vec<Arc<FactorSourceID>>,
+    pub validation: MatrixBuilderValidation,
+}
+
+impl From<rules::MatrixBuilderViolation> for MatrixBuilderViolation {
+    fn from(value: rules::MatrixBuilderViolation) -> Self {
+        Self {
+            roles: value.roles.into_iter().map(RoleKind::from).collect(),
+            factor_source_ids: value
+                .factor_source_ids
+                .iter()
+                .map(FactorSourceID::new)
+                .collect(),
+            validation: value.validation.into(),
+        }
+    }
+}
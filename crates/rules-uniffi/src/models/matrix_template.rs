@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+/// A placeholder for a FactorSourceID of kind `kind` in a `MatrixTemplate`,
+/// slots with the same `kind` and `id` are assigned the same FactorSourceID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, uniffi::Record)]
+pub struct FactorSourceTemplate {
+    pub kind: FactorSourceKind,
+    pub id: u8,
+}
+
+impl From<rules::FactorSourceTemplate> for FactorSourceTemplate {
+    fn from(value: rules::FactorSourceTemplate) -> Self {
+        Self {
+            kind: value.kind.into(),
+            id: value.id,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, uniffi::Object)]
+#[uniffi::export(Debug, Eq, Hash)]
+pub struct MatrixTemplate {
+    pub wrapped: rules::MatrixTemplate,
+}
+
+impl MatrixTemplate {
+    fn templates(factors: &[rules::FactorSourceTemplate]) -> Vec<FactorSourceTemplate> {
+        factors
+            .iter()
+            .cloned()
+            .map(FactorSourceTemplate::from)
+            .collect()
+    }
+}
+
+#[uniffi::export]
+impl MatrixTemplate {
+    pub fn get_primary_threshold(&self) -> u8 {
+        self.wrapped.primary().get_threshold()
+    }
+
+    pub fn get_number_of_days_until_auto_confirm(&self) -> u16 {
+        self.wrapped.number_of_days_until_auto_confirm()
+    }
+
+    pub fn get_primary_threshold_factors(&self) -> Vec<FactorSourceTemplate> {
+        Self::templates(self.wrapped.primary().get_threshold_factors())
+    }
+
+    pub fn get_primary_override_factors(&self) -> Vec<FactorSourceTemplate> {
+        Self::templates(self.wrapped.primary().get_override_factors())
+    }
+
+    pub fn get_recovery_factors(&self) -> Vec<FactorSourceTemplate> {
+        Self::templates(self.wrapped.recovery().get_override_factors())
+    }
+
+    pub fn get_confirmation_factors(&self) -> Vec<FactorSourceTemplate> {
+        Self::templates(self.wrapped.confirmation().get_override_factors())
+    }
+}
+
+/// A `MatrixTemplate` with a human readable name and description.
+#[derive(Debug, PartialEq, Eq, Hash, uniffi::Object)]
+#[uniffi::export(Debug, Eq, Hash)]
+pub struct NamedMatrixTemplate {
+    pub wrapped: rules::NamedMatrixTemplate,
+}
+
+#[uniffi::export]
+impl NamedMatrixTemplate {
+    pub fn get_name(&self) -> String {
+        self.wrapped.name.clone()
+    }
+
+    pub fn get_description(&self) -> String {
+        self.wrapped.description.clone()
+    }
+
+    pub fn get_template(&self) -> Arc<MatrixTemplate> {
+        Arc::new(MatrixTemplate {
+            wrapped: self.wrapped.template.clone(),
+        })
+    }
+}
+
+/// The built-in `MatrixTemplate` presets with their name and description, in
+/// the order of their config number.
+#[uniffi::export]
+pub fn matrix_template_presets() -> Vec<Arc<NamedMatrixTemplate>> {
+    rules::MatrixTemplatePreset::all()
+        .into_iter()
+        .map(|preset| {
+            Arc::new(NamedMatrixTemplate {
+                wrapped: preset.named(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets() {
+        let presets = matrix_template_presets();
+        assert_eq!(presets.len(), rules::MatrixTemplatePreset::all().len());
+
+        let first = presets.first().unwrap();
+        assert_eq!(first.get_name(), "Device and Ledger");
+        assert!(!first.get_description().is_empty());
+
+        let template = first.get_template();
+        assert_eq!(template.wrapped, rules::MatrixTemplate::config_11());
+        assert_eq!(
+            template.get_confirmation_factors(),
+            vec![FactorSourceTemplate {
+                kind: FactorSourceKind::Password,
+                id: 0
+            }]
+        );
+        assert_eq!(template.get_number_of_days_until_auto_confirm(), 14);
+    }
+}
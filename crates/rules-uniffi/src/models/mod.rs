@@ -1,3 +1,11 @@
+mod factor_source_in_matrix_builder_validation_status;
 mod factor_source_in_role_builder_validation_status;
+mod matrix_builder_validation;
+mod matrix_template;
+mod security_shield_builder_listener;
 
+pub use factor_source_in_matrix_builder_validation_status::*;
 pub use factor_source_in_role_builder_validation_status::*;
+pub use matrix_builder_validation::*;
+pub use matrix_template::*;
+pub use security_shield_builder_listener::*;
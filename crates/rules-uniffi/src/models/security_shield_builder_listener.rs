@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use crate::models::MatrixBuilderViolation;
+use crate::prelude::*;
+
+/// The state of a `SecurityShieldBuilder`, passed to its listener after each
+/// mutation, so that hosts do not need to call every getter.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct SecurityShieldBuilderSnapshot {
+    pub name: String,
+    pub primary_threshold: u8,
+    pub primary_threshold_factors: Vec<Arc<FactorSourceID>>,
+    pub primary_override_factors: Vec<Arc<FactorSourceID>>,
+    pub recovery_factors: Vec<Arc<FactorSourceID>>,
+    pub confirmation_factors: Vec<Arc<FactorSourceID>>,
+    pub number_of_days_until_auto_confirm: u16,
+
+    /// Every violation of the builder, both of each role in isolation and of
+    /// the roles in combination, empty if the builder can be built.
+    pub violations: Vec<MatrixBuilderViolation>,
+}
+
+impl SecurityShieldBuilderSnapshot {
+    pub(crate) fn new(name: String, builder: &MatrixBuilder) -> Self {
+        let factors = |factors: &Vec<sargon::FactorSourceID>| {
+            factors.iter().map(FactorSourceID::new).collect::<Vec<_>>()
+        };
+        let report = builder.validation_report();
+        Self {
+            name,
+            primary_threshold: builder.get_threshold(),
+            primary_threshold_factors: factors(builder.get_primary_threshold_factors()),
+            primary_override_factors: factors(builder.get_primary_override_factors()),
+            recovery_factors: factors(builder.get_recovery_factors()),
+            confirmation_factors: factors(builder.get_confirmation_factors()),
+            number_of_days_until_auto_confirm: builder.get_number_of_days_until_auto_confirm(),
+            violations: report
+                .violations()
+                .into_iter()
+                .cloned()
+                .map(MatrixBuilderViolation::from)
+                .collect(),
+        }
+    }
+}
+
+/// Implemented by hosts to be notified of changes of a `SecurityShieldBuilder`.
+#[uniffi::export(callback_interface)]
+pub trait SecurityShieldBuilderListener: Send + Sync {
+    /// Called after each mutation which changed the builder, including undo,
+    /// redo and changing its name.
+    fn on_change(&self, snapshot: SecurityShieldBuilderSnapshot);
+}
+
+/// Holds the listener of a `SecurityShieldBuilder`, since foreign implementations
+/// of `SecurityShieldBuilderListener` are not `Debug`.
+pub(crate) struct SecurityShieldBuilderListenerHolder(
+    pub(crate) Arc<dyn SecurityShieldBuilderListener>,
+);
+
+impl std::fmt::Debug for SecurityShieldBuilderListenerHolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecurityShieldBuilderListener").finish()
+    }
+}
@@ -3,7 +3,7 @@ use std::{borrow::Borrow, sync::Arc};
 #[cfg(test)]
 use rules::SampleValues;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
 pub enum FactorSourceKind {
     Device,
     LedgerHQHardwareWallet,
@@ -29,6 +29,38 @@ impl From<FactorSourceKind> for sargon::FactorSourceKind {
     }
 }
 
+impl From<sargon::FactorSourceKind> for FactorSourceKind {
+    fn from(value: sargon::FactorSourceKind) -> Self {
+        match value {
+            sargon::FactorSourceKind::Device => FactorSourceKind::Device,
+            sargon::FactorSourceKind::LedgerHQHardwareWallet => {
+                FactorSourceKind::LedgerHQHardwareWallet
+            }
+            sargon::FactorSourceKind::Password => FactorSourceKind::Password,
+            sargon::FactorSourceKind::OffDeviceMnemonic => FactorSourceKind::OffDeviceMnemonic,
+            sargon::FactorSourceKind::TrustedContact => FactorSourceKind::TrustedContact,
+            sargon::FactorSourceKind::SecurityQuestions => FactorSourceKind::SecurityQuestions,
+            sargon::FactorSourceKind::ArculusCard => FactorSourceKind::ArculusCard,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum RoleKind {
+    Primary,
+    Recovery,
+    Confirmation,
+}
+impl From<sargon::RoleKind> for RoleKind {
+    fn from(value: sargon::RoleKind) -> Self {
+        match value {
+            sargon::RoleKind::Primary => RoleKind::Primary,
+            sargon::RoleKind::Recovery => RoleKind::Recovery,
+            sargon::RoleKind::Confirmation => RoleKind::Confirmation,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, uniffi::Object)]
 pub struct FactorSourceID {
     pub inner: sargon::FactorSourceID,
@@ -66,4 +98,8 @@ impl FactorSourceID {
     pub fn sample_arculus_other() -> Arc<Self> {
         Self::new(sargon::FactorSourceID::sample_arculus_other())
     }
+
+    pub fn sample_password() -> Arc<Self> {
+        Self::new(sargon::FactorSourceID::sample_password())
+    }
 }
@@ -8,12 +8,12 @@ mod security_structure_of_factors;
 
 pub mod prelude {
     pub(crate) use sargon::{
-        BIP39Passphrase, BaseBaseIsFactorSource, CommonError, DerivationPreset, DisplayName,
-        FactorInstance, FactorInstances, FactorSource, FactorSourceID, FactorSourceIDFromHash,
-        FactorSourceKind, FactorSources, HasRoleKindObjectSafe, HasSampleValues,
-        HierarchicalDeterministicFactorInstance, Identifiable, IndexMap, IndexSet,
-        IsMaybeKeySpaceAware, IsSecurityStateAware, KeySpace, Mnemonic, MnemonicWithPassphrase,
-        RoleKind,
+        AddressOfAccountOrPersona, BIP39Passphrase, BaseBaseIsFactorSource, CAP26EntityKind,
+        CommonError, DerivationPreset, DisplayName, FactorInstance, FactorInstances, FactorSource,
+        FactorSourceID, FactorSourceIDFromHash, FactorSourceKind, FactorSources,
+        HasRoleKindObjectSafe, HasSampleValues, HierarchicalDeterministicFactorInstance,
+        Identifiable, IndexMap, IndexSet, IsMaybeKeySpaceAware, IsNetworkAware,
+        IsSecurityStateAware, KeySpace, Mnemonic, MnemonicWithPassphrase, NetworkID, RoleKind,
     };
 
     pub(crate) use itertools::*;
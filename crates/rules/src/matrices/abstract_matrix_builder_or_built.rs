@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AbstractMatrixBuilderOrBuilt<F, T, U> {
     #[serde(skip)]
@@ -19,6 +19,45 @@ impl<F, T, U> AbstractMatrixBuilderOrBuilt<F, T, U> {
 
 pub type AbstractMatrixBuilt<F> = AbstractMatrixBuilderOrBuilt<F, (), ()>;
 
+/// The serialized form of an `AbstractMatrixBuilderOrBuilt`, deserialized
+/// without any validation.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UnvalidatedMatrix<F> {
+    primary_role: UnvalidatedRole<F>,
+    recovery_role: UnvalidatedRole<F>,
+    confirmation_role: UnvalidatedRole<F>,
+    number_of_days_until_auto_confirm: u16,
+}
+
+impl<F, T, U> AbstractMatrixBuilderOrBuilt<F, T, U> {
+    /// N.B. `unvalidated` is not validated, use `validate_deserialized`.
+    pub(crate) fn from_unvalidated(unvalidated: UnvalidatedMatrix<F>) -> Self {
+        Self {
+            built: PhantomData,
+            primary_role: AbstractRoleBuilderOrBuilt::from_unvalidated(unvalidated.primary_role),
+            recovery_role: AbstractRoleBuilderOrBuilt::from_unvalidated(unvalidated.recovery_role),
+            confirmation_role: AbstractRoleBuilderOrBuilt::from_unvalidated(
+                unvalidated.confirmation_role,
+            ),
+            number_of_days_until_auto_confirm: unvalidated.number_of_days_until_auto_confirm,
+        }
+    }
+}
+
+impl<'de, F: Deserialize<'de>, T, U> Deserialize<'de> for AbstractMatrixBuilderOrBuilt<F, T, U>
+where
+    Self: IsValidatedOnDeserialization,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let matrix = Self::from_unvalidated(UnvalidatedMatrix::deserialize(deserializer)?);
+        matrix
+            .validate_deserialized()
+            .map_err(serde::de::Error::custom)?;
+        Ok(matrix)
+    }
+}
+
 impl<F> AbstractMatrixBuilt<F> {
     pub fn primary(&self) -> &AbstractBuiltRoleWithFactor<{ ROLE_PRIMARY }, F> {
         &self.primary_role
@@ -31,6 +70,10 @@ impl<F> AbstractMatrixBuilt<F> {
     pub fn confirmation(&self) -> &AbstractBuiltRoleWithFactor<{ ROLE_CONFIRMATION }, F> {
         &self.confirmation_role
     }
+
+    pub fn number_of_days_until_auto_confirm(&self) -> u16 {
+        self.number_of_days_until_auto_confirm
+    }
 }
 
 impl<F: std::cmp::Eq + std::hash::Hash> AbstractMatrixBuilt<F> {
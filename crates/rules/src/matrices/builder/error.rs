@@ -44,6 +44,103 @@ pub enum MatrixBuilderValidation {
     CombinationViolation(#[from] MatrixRolesInCombinationViolation),
 }
 
+impl MatrixBuilderValidation {
+    /// Returns `true` if this violation might be resolved by further mutations
+    /// of the builder, e.g. adding more factors.
+    pub(crate) fn is_not_yet_valid(&self) -> bool {
+        match self {
+            Self::RoleInIsolation { violation, .. } => {
+                matches!(violation, RoleBuilderValidation::NotYetValid(_))
+            }
+            Self::CombinationViolation(violation) => {
+                matches!(violation, MatrixRolesInCombinationViolation::NotYetValid(_))
+            }
+        }
+    }
+}
+
+/// Materialization of a `MatrixTemplate` failed since no FactorSourceID of
+/// the kind of `template` was left to assign to it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("Unable to assign a factor source to template slot {:?} #{} in role {role:?}, supplied {supplied} factor source(s) of that kind but {needed} needed", .template.kind, .template.id)]
+pub struct MatrixTemplateMaterializationError {
+    /// The template slot which could not be assigned a FactorSourceID.
+    pub template: FactorSourceTemplate,
+
+    /// The role the template slot belongs to.
+    pub role: RoleKind,
+
+    /// The number of distinct FactorSourceIDs of the kind of `template` supplied.
+    pub supplied: usize,
+
+    /// The number of distinct FactorSourceIDs of the kind of `template` the
+    /// whole matrix template needs.
+    pub needed: usize,
+}
+
+/// Materialization of a `MatrixTemplate` using explicit assignments of template
+/// slots to FactorSourceIDs failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum MatrixTemplateAssignmentError {
+    #[error("Template slot {template:?} is not part of the template")]
+    SlotNotInTemplate { template: FactorSourceTemplate },
+
+    #[error(
+        "FactorSourceID {factor_source_id:?} is not of the kind of template slot {template:?}"
+    )]
+    FactorSourceKindMismatch {
+        template: FactorSourceTemplate,
+        factor_source_id: FactorSourceID,
+    },
+
+    #[error("FactorSourceID {factor_source_id:?} is assigned to multiple template slots")]
+    FactorSourceAssignedToMultipleSlots { factor_source_id: FactorSourceID },
+
+    #[error(transparent)]
+    Materialization(#[from] MatrixTemplateMaterializationError),
+}
+
+/// Validating deserialization of a `MatrixOfFactorSourceIds`, or a type
+/// containing one, failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MatrixDeserializationError {
+    #[error("Failed to deserialize JSON at line {line} column {column}: {reason}")]
+    InvalidJson {
+        category: serde_json::error::Category,
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+
+    #[error("Deserialized matrix is invalid: {0}")]
+    InvalidMatrix(#[from] MatrixBuilderValidation),
+}
+
+impl From<serde_json::Error> for MatrixDeserializationError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::InvalidJson {
+            category: value.classify(),
+            line: value.line(),
+            column: value.column(),
+            reason: value.to_string(),
+        }
+    }
+}
+
+/// A FactorInstance of a role was not in the `Securified` key space.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Factor instance in {list:?} list of role {role:?} is in key space {key_space:?}, expected Securified, factor instance: {factor_instance:?}")]
+pub struct FactorNotSecurified {
+    pub role: RoleKind,
+    pub list: FactorListKind,
+
+    /// The key space of the offending factor instance.
+    pub key_space: KeySpace,
+
+    /// The offending factor instance.
+    pub factor_instance: HierarchicalDeterministicFactorInstance,
+}
+
 pub(crate) trait IntoMatrixErr<T> {
     fn into_matrix_err(self, role: RoleKind) -> Result<T, MatrixBuilderValidation>;
 }
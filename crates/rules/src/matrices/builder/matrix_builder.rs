@@ -8,12 +8,45 @@ pub type MatrixBuilderBuildResult = Result<MatrixOfFactorSourceIds, MatrixBuilde
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Built;
 
+/// The validation status of adding a factor source to some role of a
+/// `MatrixBuilder`, taking into account both the rules of the role in isolation
+/// and the rules of the roles in combination.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FactorSourceInMatrixBuilderValidationStatus {
+    pub role: RoleKind,
+    pub factor_source_id: FactorSourceID,
+    pub validation: MatrixBuilderMutateResult,
+}
+
+impl FactorSourceInMatrixBuilderValidationStatus {
+    pub(crate) fn new(
+        role: RoleKind,
+        factor_source_id: FactorSourceID,
+        validation: MatrixBuilderMutateResult,
+    ) -> Self {
+        Self {
+            role,
+            factor_source_id,
+            validation,
+        }
+    }
+}
+
 pub type MatrixBuilder = AbstractMatrixBuilderOrBuilt<
     FactorSourceID,
     MatrixOfFactorSourceIds,
     Built, // this is HACKY
 >;
 
+impl IsValidatedOnDeserialization for MatrixBuilder {
+    type Error = std::convert::Infallible;
+
+    /// A builder is allowed to be invalid, it is validated when built.
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
 // ==================
 // ===== PUBLIC =====
 // ==================
@@ -28,6 +61,21 @@ impl MatrixBuilder {
         }
     }
 
+    /// Creates a builder seeded with the roles and number of days until auto
+    /// confirm of `matrix`, e.g. for editing an existing shield.
+    ///
+    /// N.B. `matrix` is not validated, use `validate` or `validation_report` to
+    /// learn if it is valid.
+    pub fn with_matrix(matrix: MatrixOfFactorSourceIds) -> Self {
+        Self {
+            built: PhantomData,
+            primary_role: PrimaryRoleBuilder::from_built(matrix.primary_role),
+            recovery_role: RecoveryRoleBuilder::from_built(matrix.recovery_role),
+            confirmation_role: ConfirmationRoleBuilder::from_built(matrix.confirmation_role),
+            number_of_days_until_auto_confirm: matrix.number_of_days_until_auto_confirm,
+        }
+    }
+
     pub fn build(self) -> MatrixBuilderBuildResult {
         self.validate_combination()?;
 
@@ -130,6 +178,58 @@ impl MatrixBuilder {
             )
     }
 
+    /// Like `validation_for_addition_of_factor_source_to_primary_threshold_for_each`
+    /// but also takes the rules of the roles in combination into account.
+    pub fn validation_in_combination_for_addition_of_factor_source_to_primary_threshold_for_each(
+        &self,
+        factor_sources: &IndexSet<FactorSourceID>,
+    ) -> IndexSet<FactorSourceInMatrixBuilderValidationStatus> {
+        self.validation_in_combination_for_addition_of_factor_source_for_each(
+            RoleKind::Primary,
+            factor_sources,
+            Self::add_factor_source_to_primary_threshold,
+        )
+    }
+
+    /// Like `validation_for_addition_of_factor_source_to_primary_override_for_each`
+    /// but also takes the rules of the roles in combination into account.
+    pub fn validation_in_combination_for_addition_of_factor_source_to_primary_override_for_each(
+        &self,
+        factor_sources: &IndexSet<FactorSourceID>,
+    ) -> IndexSet<FactorSourceInMatrixBuilderValidationStatus> {
+        self.validation_in_combination_for_addition_of_factor_source_for_each(
+            RoleKind::Primary,
+            factor_sources,
+            Self::add_factor_source_to_primary_override,
+        )
+    }
+
+    /// Like `validation_for_addition_of_factor_source_to_recovery_override_for_each`
+    /// but also takes the rules of the roles in combination into account.
+    pub fn validation_in_combination_for_addition_of_factor_source_to_recovery_override_for_each(
+        &self,
+        factor_sources: &IndexSet<FactorSourceID>,
+    ) -> IndexSet<FactorSourceInMatrixBuilderValidationStatus> {
+        self.validation_in_combination_for_addition_of_factor_source_for_each(
+            RoleKind::Recovery,
+            factor_sources,
+            Self::add_factor_source_to_recovery_override,
+        )
+    }
+
+    /// Like `validation_for_addition_of_factor_source_to_confirmation_override_for_each`
+    /// but also takes the rules of the roles in combination into account.
+    pub fn validation_in_combination_for_addition_of_factor_source_to_confirmation_override_for_each(
+        &self,
+        factor_sources: &IndexSet<FactorSourceID>,
+    ) -> IndexSet<FactorSourceInMatrixBuilderValidationStatus> {
+        self.validation_in_combination_for_addition_of_factor_source_for_each(
+            RoleKind::Confirmation,
+            factor_sources,
+            Self::add_factor_source_to_confirmation_override,
+        )
+    }
+
     pub fn validate_each_role_in_isolation(&self) -> MatrixBuilderMutateResult {
         self.primary_role
             .validate()
@@ -149,6 +249,32 @@ impl MatrixBuilder {
         Ok(())
     }
 
+    /// Like `validate` but does not stop at the first violation, instead every
+    /// violation of each role in isolation and of the roles in combination is
+    /// collected into a report.
+    pub fn validation_report(&self) -> MatrixBuilderValidationReport {
+        let primary = self
+            .primary_role
+            .violations()
+            .into_iter()
+            .map(|v| MatrixBuilderViolation::role_in_isolation(RoleKind::Primary, v));
+        let recovery = self
+            .recovery_role
+            .violations()
+            .into_iter()
+            .map(|v| MatrixBuilderViolation::role_in_isolation(RoleKind::Recovery, v));
+        let confirmation = self
+            .confirmation_role
+            .violations()
+            .into_iter()
+            .map(|v| MatrixBuilderViolation::role_in_isolation(RoleKind::Confirmation, v));
+
+        MatrixBuilderValidationReport::new(
+            primary.chain(recovery).chain(confirmation),
+            self.combination_violations(),
+        )
+    }
+
     /// Adds the factor source to the primary role threshold list.
     pub fn add_factor_source_to_primary_threshold(
         &mut self,
@@ -227,6 +353,31 @@ impl MatrixBuilder {
         self.number_of_days_until_auto_confirm
     }
 
+    /// Performs `mutation` by calling the corresponding mutating method of `self`.
+    pub fn mutate(&mut self, mutation: MatrixBuilderMutation) -> MatrixBuilderMutateResult {
+        match mutation {
+            MatrixBuilderMutation::AddFactorSourceToPrimaryThreshold(factor_source_id) => {
+                self.add_factor_source_to_primary_threshold(factor_source_id)
+            }
+            MatrixBuilderMutation::AddFactorSourceToPrimaryOverride(factor_source_id) => {
+                self.add_factor_source_to_primary_override(factor_source_id)
+            }
+            MatrixBuilderMutation::AddFactorSourceToRecoveryOverride(factor_source_id) => {
+                self.add_factor_source_to_recovery_override(factor_source_id)
+            }
+            MatrixBuilderMutation::AddFactorSourceToConfirmationOverride(factor_source_id) => {
+                self.add_factor_source_to_confirmation_override(factor_source_id)
+            }
+            MatrixBuilderMutation::RemoveFactor(factor_source_id) => {
+                self.remove_factor(&factor_source_id)
+            }
+            MatrixBuilderMutation::SetThreshold(threshold) => self.set_threshold(threshold),
+            MatrixBuilderMutation::SetNumberOfDaysUntilAutoConfirm(number_of_days) => {
+                self.set_number_of_days_until_auto_confirm(number_of_days)
+            }
+        }
+    }
+
     /// Removes `factor_source_id` from all three roles, if not found in any an error
     /// is thrown.
     ///
@@ -274,59 +425,140 @@ impl MatrixBuilder {
 // ==== PRIVATE =====
 // ==================
 impl MatrixBuilder {
-    fn validate_if_primary_has_single_it_must_not_be_used_by_any_other_role(
+    fn violation_if_primary_has_single_it_must_not_be_used_by_any_other_role(
         &self,
-    ) -> MatrixBuilderMutateResult {
-        let primary_has_single_factor = self.primary_role.all_factors().len() == 1;
-        if primary_has_single_factor {
-            let primary_factors = self.primary_role.all_factors();
-            let primary_factor = primary_factors.first().unwrap();
-            let recovery_set = HashSet::<_>::from_iter(self.recovery_role.get_override_factors());
-            let confirmation_set =
-                HashSet::<_>::from_iter(self.confirmation_role.get_override_factors());
-            if recovery_set.contains(primary_factor) || confirmation_set.contains(primary_factor) {
-                return Err(MatrixBuilderValidation::CombinationViolation(
-                    MatrixRolesInCombinationViolation::NotYetValid(MatrixRolesInCombinationNotYetValid::SingleFactorUsedInPrimaryMustNotBeUsedInAnyOtherRole),
-                ));
-            }
+    ) -> Option<MatrixBuilderViolation> {
+        let primary_factors = self.primary_role.all_factors();
+        let primary_has_single_factor = primary_factors.len() == 1;
+        if !primary_has_single_factor {
+            return None;
         }
-        Ok(())
+        let primary_factor = *primary_factors.first().unwrap();
+        let mut roles = vec![RoleKind::Primary];
+        if self
+            .recovery_role
+            .get_override_factors()
+            .contains(primary_factor)
+        {
+            roles.push(RoleKind::Recovery);
+        }
+        if self
+            .confirmation_role
+            .get_override_factors()
+            .contains(primary_factor)
+        {
+            roles.push(RoleKind::Confirmation);
+        }
+        if roles.len() == 1 {
+            return None;
+        }
+        Some(MatrixBuilderViolation::combination(
+            roles,
+            [*primary_factor],
+            MatrixRolesInCombinationNotYetValid::SingleFactorUsedInPrimaryMustNotBeUsedInAnyOtherRole,
+        ))
     }
 
-    fn validate_no_factor_may_be_used_in_both_recovery_and_confirmation(
+    fn violation_if_any_factor_used_in_both_recovery_and_confirmation(
         &self,
-    ) -> MatrixBuilderMutateResult {
-        let recovery_set = HashSet::<_>::from_iter(self.recovery_role.get_override_factors());
+    ) -> Option<MatrixBuilderViolation> {
         let confirmation_set =
             HashSet::<_>::from_iter(self.confirmation_role.get_override_factors());
-        let intersection = recovery_set
-            .intersection(&confirmation_set)
-            .collect::<HashSet<_>>();
+        let intersection = self
+            .recovery_role
+            .get_override_factors()
+            .iter()
+            .filter(|f| confirmation_set.contains(f))
+            .cloned()
+            .collect_vec();
         if intersection.is_empty() {
-            Ok(())
-        } else {
-            Err(MatrixBuilderValidation::CombinationViolation(
-                MatrixRolesInCombinationViolation::ForeverInvalid(
-                    MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap,
-                ),
-            ))
+            return None;
         }
+        Some(MatrixBuilderViolation::combination(
+            [RoleKind::Recovery, RoleKind::Confirmation],
+            intersection,
+            MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap,
+        ))
     }
 
-    fn validate_number_of_days_until_auto_confirm(&self) -> MatrixBuilderMutateResult {
-        if self.number_of_days_until_auto_confirm == 0 {
-            return Err(MatrixBuilderValidation::CombinationViolation(
-                MatrixRolesInCombinationViolation::Basic(
-                    MatrixRolesInCombinationBasicViolation::NumberOfDaysUntilAutoConfirmMustBeGreaterThanZero,
-                ),
-            ));
+    fn violation_if_number_of_days_until_auto_confirm_is_zero(
+        &self,
+    ) -> Option<MatrixBuilderViolation> {
+        if self.number_of_days_until_auto_confirm != 0 {
+            return None;
         }
-        Ok(())
+        Some(MatrixBuilderViolation::combination(
+            [],
+            [],
+            MatrixRolesInCombinationBasicViolation::NumberOfDaysUntilAutoConfirmMustBeGreaterThanZero,
+        ))
+    }
+
+    fn validate_number_of_days_until_auto_confirm(&self) -> MatrixBuilderMutateResult {
+        self.violation_if_number_of_days_until_auto_confirm_is_zero()
+            .map_or(Ok(()), |v| Err(v.validation))
+    }
+
+    /// For each factor source in `factor_sources`, simulates adding it to a clone
+    /// of `self` using `add`, and returns the validation status of the role
+    /// in isolation together with the violations of the roles in combination
+    /// involving the factor source. Violations which are not "not yet valid"
+    /// take precedence, since they can never be resolved.
+    fn validation_in_combination_for_addition_of_factor_source_for_each(
+        &self,
+        role: RoleKind,
+        factor_sources: &IndexSet<FactorSourceID>,
+        add: impl Fn(&mut Self, FactorSourceID) -> MatrixBuilderMutateResult,
+    ) -> IndexSet<FactorSourceInMatrixBuilderValidationStatus> {
+        factor_sources
+            .iter()
+            .map(|factor_source_id| {
+                let mut simulation = self.clone();
+                let role_validation = add(&mut simulation, *factor_source_id);
+                let violations = role_validation
+                    .err()
+                    .into_iter()
+                    .chain(
+                        simulation
+                            .combination_violations()
+                            .into_iter()
+                            .filter(|v| v.factor_source_ids.contains(factor_source_id))
+                            .map(|v| v.validation),
+                    )
+                    .collect_vec();
+                let validation = violations
+                    .iter()
+                    .find(|v| !v.is_not_yet_valid())
+                    .or(violations.first())
+                    .map_or(Ok(()), |v| Err(*v));
+                FactorSourceInMatrixBuilderValidationStatus::new(
+                    role,
+                    *factor_source_id,
+                    validation,
+                )
+            })
+            .collect()
+    }
+
+    /// Every violation of the roles in combination, see `validate_combination`
+    /// for the rules.
+    fn combination_violations(&self) -> Vec<MatrixBuilderViolation> {
+        [
+            self.violation_if_primary_has_single_it_must_not_be_used_by_any_other_role(),
+            self.violation_if_any_factor_used_in_both_recovery_and_confirmation(),
+            // N.B. the third 3:
+            // "3. No factor may be used in both the `Primary` threshold and `Primary` override"
+            // is already enforced by the RoleBuilder
+            self.violation_if_number_of_days_until_auto_confirm_is_zero(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 
     /// Security Shield Rules
     /// In addition to the factor/role rules above, the wallet must enforce certain rules for combinations of
-    /// factors across the three roles. The construction method described in the next section will automatically
+    /// factors across the three roles. The automatic construction `MatrixBuilder::auto_build` will
     /// always follow these rules. A user may however choose to manually add/remove factors from their Shield
     /// configuration and so the wallet must evaluate these rules and inform the user when the combination they
     /// have chosen cannot be used. The wallet should never allow a user to complete a Shield configuration that
@@ -337,15 +569,9 @@ impl MatrixBuilder {
     /// 3. No factor may be used in both the `Primary` threshold and `Primary` override
     /// 4. Number of days until auto confirm is greater than zero
     fn validate_combination(&self) -> MatrixBuilderMutateResult {
-        self.validate_if_primary_has_single_it_must_not_be_used_by_any_other_role()?;
-        self.validate_no_factor_may_be_used_in_both_recovery_and_confirmation()?;
-
-        // N.B. the third 3:
-        // "3. No factor may be used in both the `Primary` threshold and `Primary` override"
-        // is already enforced by the RoleBuilder
-
-        self.validate_number_of_days_until_auto_confirm()?;
-
-        Ok(())
+        self.combination_violations()
+            .into_iter()
+            .next()
+            .map_or(Ok(()), |v| Err(v.validation))
     }
 }
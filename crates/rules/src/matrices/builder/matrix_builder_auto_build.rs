@@ -0,0 +1,404 @@
+use crate::prelude::*;
+
+/// Why a factor source was assigned to a certain role by `MatrixBuilder::auto_build`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AutoBuildReason {
+    /// A device factor is always at hand, so it is used for everyday signing.
+    PrimaryDevice,
+
+    /// A hardware wallet (Ledger or Arculus) is used for signing, together
+    /// with the device factor if there is one, so that a stolen phone alone
+    /// cannot be used to sign.
+    PrimaryHardwareWallet,
+
+    /// No hardware wallet was available, so an off device mnemonic is used
+    /// for signing instead, together with the device factor if there is one.
+    PrimaryOffDeviceMnemonic,
+
+    /// Only one other factor was available for the primary role, a password
+    /// is not allowed alone, but is allowed together with another factor.
+    PrimaryPasswordAsAdditionalFactor,
+
+    /// Device factors, hardware wallets and trusted contacts can be used to
+    /// recover access to the entity.
+    RecoveryFactor,
+
+    /// Factors based on information known by the user (passwords, security
+    /// questions and off device mnemonics) are used to confirm a recovery.
+    ConfirmationInformationFactor,
+
+    /// No factor based on information known by the user was available, so one
+    /// recovery factor was moved to confirmation instead.
+    ConfirmationMovedFromRecovery,
+}
+
+/// A choice made by `MatrixBuilder::auto_build`, i.e. the assignment of a
+/// factor source to a factor list of a role, and the reason for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AutoBuildChoice {
+    pub factor_source_id: FactorSourceID,
+    pub role: RoleKind,
+    pub list: FactorListKind,
+    pub reason: AutoBuildReason,
+}
+
+impl AutoBuildChoice {
+    fn new(
+        factor_source_id: FactorSourceID,
+        role: RoleKind,
+        list: FactorListKind,
+        reason: AutoBuildReason,
+    ) -> Self {
+        Self {
+            factor_source_id,
+            role,
+            list,
+            reason,
+        }
+    }
+
+    fn primary(factor_source_id: FactorSourceID, reason: AutoBuildReason) -> Self {
+        Self::new(
+            factor_source_id,
+            RoleKind::Primary,
+            FactorListKind::Threshold,
+            reason,
+        )
+    }
+
+    fn recovery(factor_source_id: FactorSourceID) -> Self {
+        Self::new(
+            factor_source_id,
+            RoleKind::Recovery,
+            FactorListKind::Override,
+            AutoBuildReason::RecoveryFactor,
+        )
+    }
+
+    fn confirmation(factor_source_id: FactorSourceID, reason: AutoBuildReason) -> Self {
+        Self::new(
+            factor_source_id,
+            RoleKind::Confirmation,
+            FactorListKind::Override,
+            reason,
+        )
+    }
+
+    fn mutation(&self) -> MatrixBuilderMutation {
+        match (self.role, self.list) {
+            (RoleKind::Primary, FactorListKind::Threshold) => {
+                MatrixBuilderMutation::AddFactorSourceToPrimaryThreshold(self.factor_source_id)
+            }
+            (RoleKind::Primary, FactorListKind::Override) => {
+                MatrixBuilderMutation::AddFactorSourceToPrimaryOverride(self.factor_source_id)
+            }
+            (RoleKind::Recovery, _) => {
+                MatrixBuilderMutation::AddFactorSourceToRecoveryOverride(self.factor_source_id)
+            }
+            (RoleKind::Confirmation, _) => {
+                MatrixBuilderMutation::AddFactorSourceToConfirmationOverride(self.factor_source_id)
+            }
+        }
+    }
+}
+
+/// A matrix automatically built by `MatrixBuilder::auto_build`, together with
+/// the choices made to build it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoBuiltMatrix {
+    pub matrix: MatrixOfFactorSourceIds,
+
+    /// Every assignment of a factor source to a role, in the order they were
+    /// made.
+    pub choices: Vec<AutoBuildChoice>,
+}
+
+impl MatrixBuilder {
+    /// Automatically builds a matrix using the available `factor_sources`,
+    /// see `auto_build_with_factor_source_ids`.
+    pub fn auto_build(
+        factor_sources: &FactorSources,
+    ) -> Result<AutoBuiltMatrix, MatrixBuilderValidation> {
+        Self::auto_build_with_factor_source_ids(factor_sources.iter().map(|f| f.id()))
+    }
+
+    /// Automatically builds a matrix using the available `factor_source_ids`,
+    /// following the rules of each role in isolation and of the roles in
+    /// combination:
+    ///
+    /// 1. Primary uses a device factor and a hardware wallet (or off device
+    ///    mnemonic), with a threshold of all of them. If only one of those is
+    ///    available a password is added as well.
+    /// 2. Confirmation uses all factors based on information known by the user,
+    ///    i.e. passwords, security questions and off device mnemonics.
+    /// 3. Recovery uses all device factors, hardware wallets and trusted contacts.
+    /// 4. If Primary has a single factor, it is not used in any other role.
+    /// 5. If no factor was assigned to Confirmation, the last recovery factor
+    ///    (not being a trusted contact) is moved to Confirmation, if Recovery
+    ///    has at least two factors.
+    ///
+    /// # Throws
+    /// If no valid matrix can be built with the available factors, the validation
+    /// error of the best effort matrix is thrown, e.g. if only a single device
+    /// factor is available, Recovery will have no factors.
+    pub fn auto_build_with_factor_source_ids(
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+    ) -> Result<AutoBuiltMatrix, MatrixBuilderValidation> {
+        let factor_source_ids = factor_source_ids.into_iter().unique().collect_vec();
+        let choices = Self::auto_build_choices(&factor_source_ids);
+
+        let mut builder = Self::new();
+        for choice in choices.iter() {
+            match builder.mutate(choice.mutation()) {
+                Ok(()) => {}
+                Err(validation) if validation.is_not_yet_valid() => {}
+                Err(validation) => return Err(validation),
+            }
+        }
+        let threshold = builder.get_primary_threshold_factors().len() as u8;
+        match builder.set_threshold(threshold) {
+            Ok(()) => {}
+            Err(validation) if validation.is_not_yet_valid() => {}
+            Err(validation) => return Err(validation),
+        }
+
+        let matrix = builder.build()?;
+        Ok(AutoBuiltMatrix { matrix, choices })
+    }
+}
+
+// ==================
+// ==== PRIVATE =====
+// ==================
+impl MatrixBuilder {
+    fn auto_build_choices(factor_source_ids: &[FactorSourceID]) -> Vec<AutoBuildChoice> {
+        use AutoBuildReason::*;
+        let of_kinds = |kinds: &[FactorSourceKind]| -> Vec<FactorSourceID> {
+            factor_source_ids
+                .iter()
+                .filter(|f| kinds.contains(&f.get_factor_source_kind()))
+                .cloned()
+                .collect_vec()
+        };
+        let devices = of_kinds(&[FactorSourceKind::Device]);
+        let hardware_wallets = of_kinds(&[
+            FactorSourceKind::LedgerHQHardwareWallet,
+            FactorSourceKind::ArculusCard,
+        ]);
+        let passwords = of_kinds(&[FactorSourceKind::Password]);
+        let off_device_mnemonics = of_kinds(&[FactorSourceKind::OffDeviceMnemonic]);
+        let trusted_contacts = of_kinds(&[FactorSourceKind::TrustedContact]);
+        let information_factors = of_kinds(&[
+            FactorSourceKind::Password,
+            FactorSourceKind::SecurityQuestions,
+            FactorSourceKind::OffDeviceMnemonic,
+        ]);
+
+        // Primary
+        let mut primary = Vec::new();
+        if let Some(device) = devices.first() {
+            primary.push(AutoBuildChoice::primary(*device, PrimaryDevice));
+        }
+        if let Some(hardware_wallet) = hardware_wallets.first() {
+            primary.push(AutoBuildChoice::primary(
+                *hardware_wallet,
+                PrimaryHardwareWallet,
+            ));
+        } else if let Some(off_device_mnemonic) = off_device_mnemonics.first() {
+            primary.push(AutoBuildChoice::primary(
+                *off_device_mnemonic,
+                PrimaryOffDeviceMnemonic,
+            ));
+        }
+        if primary.len() == 1 {
+            if let Some(password) = passwords.first() {
+                primary.push(AutoBuildChoice::primary(
+                    *password,
+                    PrimaryPasswordAsAdditionalFactor,
+                ));
+            }
+        }
+        let single_primary_factor = if primary.len() == 1 {
+            Some(primary[0].factor_source_id)
+        } else {
+            None
+        };
+        let is_allowed_outside_primary = |f: &FactorSourceID| Some(*f) != single_primary_factor;
+
+        // Confirmation
+        let mut confirmation = information_factors
+            .into_iter()
+            .filter(is_allowed_outside_primary)
+            .map(|f| AutoBuildChoice::confirmation(f, ConfirmationInformationFactor))
+            .collect_vec();
+
+        // Recovery
+        let mut recovery = devices
+            .into_iter()
+            .chain(hardware_wallets)
+            .chain(trusted_contacts)
+            .filter(is_allowed_outside_primary)
+            .map(AutoBuildChoice::recovery)
+            .collect_vec();
+
+        if confirmation.is_empty() && recovery.len() > 1 {
+            if let Some(index) = recovery.iter().rposition(|c| {
+                c.factor_source_id.get_factor_source_kind() != FactorSourceKind::TrustedContact
+            }) {
+                let moved = recovery.remove(index);
+                confirmation.push(AutoBuildChoice::confirmation(
+                    moved.factor_source_id,
+                    ConfirmationMovedFromRecovery,
+                ));
+            }
+        }
+
+        primary
+            .into_iter()
+            .chain(recovery)
+            .chain(confirmation)
+            .collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::upper_case_acronyms)]
+    type SUT = MatrixBuilder;
+
+    #[test]
+    fn device_ledger_password() {
+        let built = SUT::auto_build_with_factor_source_ids([
+            FactorSourceID::sample_password(),
+            FactorSourceID::sample_ledger(),
+            FactorSourceID::sample_device(),
+        ])
+        .unwrap();
+        pretty_assertions::assert_eq!(built.matrix, MatrixOfFactorSourceIds::sample_config_11());
+        assert_eq!(
+            built.choices.iter().map(|c| c.reason).collect_vec(),
+            vec![
+                AutoBuildReason::PrimaryDevice,
+                AutoBuildReason::PrimaryHardwareWallet,
+                AutoBuildReason::RecoveryFactor,
+                AutoBuildReason::RecoveryFactor,
+                AutoBuildReason::ConfirmationInformationFactor,
+            ]
+        );
+    }
+
+    #[test]
+    fn device_and_two_ledgers_moves_last_ledger_to_confirmation() {
+        let built = SUT::auto_build_with_factor_source_ids([
+            FactorSourceID::sample_device(),
+            FactorSourceID::sample_ledger(),
+            FactorSourceID::sample_ledger_other(),
+        ])
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            built.matrix,
+            MatrixOfFactorSourceIds::with_roles(
+                RoleWithFactorSourceIds::primary_with_factors(
+                    2,
+                    [
+                        FactorSourceID::sample_device(),
+                        FactorSourceID::sample_ledger()
+                    ],
+                    []
+                ),
+                RoleWithFactorSourceIds::recovery_with_factors([
+                    FactorSourceID::sample_device(),
+                    FactorSourceID::sample_ledger()
+                ]),
+                RoleWithFactorSourceIds::confirmation_with_factors([
+                    FactorSourceID::sample_ledger_other()
+                ]),
+            )
+        );
+        assert_eq!(
+            built.choices.last().unwrap().reason,
+            AutoBuildReason::ConfirmationMovedFromRecovery
+        );
+    }
+
+    #[test]
+    fn device_and_password_uses_password_in_primary() {
+        let built = SUT::auto_build_with_factor_source_ids([
+            FactorSourceID::sample_device(),
+            FactorSourceID::sample_password(),
+            FactorSourceID::sample_trusted_contact(),
+        ])
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            built.matrix,
+            MatrixOfFactorSourceIds::with_roles(
+                RoleWithFactorSourceIds::primary_with_factors(
+                    2,
+                    [
+                        FactorSourceID::sample_device(),
+                        FactorSourceID::sample_password()
+                    ],
+                    []
+                ),
+                RoleWithFactorSourceIds::recovery_with_factors([
+                    FactorSourceID::sample_device(),
+                    FactorSourceID::sample_trusted_contact()
+                ]),
+                RoleWithFactorSourceIds::confirmation_with_factors([
+                    FactorSourceID::sample_password()
+                ]),
+            )
+        );
+        assert_eq!(
+            built.choices[1].reason,
+            AutoBuildReason::PrimaryPasswordAsAdditionalFactor
+        );
+    }
+
+    #[test]
+    fn single_primary_factor_is_not_used_in_other_roles() {
+        let built = SUT::auto_build_with_factor_source_ids([
+            FactorSourceID::sample_device(),
+            FactorSourceID::sample_trusted_contact(),
+            FactorSourceID::sample_security_questions(),
+        ])
+        .unwrap();
+        pretty_assertions::assert_eq!(built.matrix, MatrixOfFactorSourceIds::sample_config_60());
+    }
+
+    #[test]
+    fn all_samples_is_valid() {
+        let built =
+            SUT::auto_build_with_factor_source_ids(*ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD)
+                .unwrap();
+        assert!(SUT::with_matrix(built.matrix).validate().is_ok());
+    }
+
+    #[test]
+    fn all_factor_sources_is_valid() {
+        let built = SUT::auto_build(&FactorSources::sample_values_all()).unwrap();
+        assert!(SUT::with_matrix(built.matrix).validate().is_ok());
+    }
+
+    #[test]
+    fn single_device_is_err() {
+        let res = SUT::auto_build_with_factor_source_ids([FactorSourceID::sample_device()]);
+        assert_eq!(
+            res,
+            Err(MatrixBuilderValidation::RoleInIsolation {
+                role: RoleKind::Recovery,
+                violation: RoleBuilderValidation::NotYetValid(
+                    NotYetValidReason::RoleMustHaveAtLeastOneFactor
+                )
+            })
+        );
+    }
+
+    #[test]
+    fn empty_is_err() {
+        let res = SUT::auto_build_with_factor_source_ids([]);
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,312 @@
+use crate::prelude::*;
+
+/// A mutation of a `MatrixBuilder`, i.e. a call to one of its mutating methods,
+/// which can be recorded in a `MatrixBuilderHistory` to later be undone/redone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatrixBuilderMutation {
+    AddFactorSourceToPrimaryThreshold(FactorSourceID),
+    AddFactorSourceToPrimaryOverride(FactorSourceID),
+    AddFactorSourceToRecoveryOverride(FactorSourceID),
+    AddFactorSourceToConfirmationOverride(FactorSourceID),
+    RemoveFactor(FactorSourceID),
+    SetThreshold(u8),
+    SetNumberOfDaysUntilAutoConfirm(u16),
+}
+
+/// A change of a `MatrixBuilder` caused by a mutation, which was not explicitly
+/// requested by the mutation itself, e.g. `remove_factor` lowering the threshold
+/// of the primary role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatrixBuilderSideEffect {
+    /// The factor source was removed from the factor list of kind `list` of `role`.
+    FactorSourceRemovedFromRole {
+        role: RoleKind,
+        list: FactorListKind,
+        factor_source_id: FactorSourceID,
+    },
+
+    /// The threshold of the primary role was changed from `from` to `to`.
+    PrimaryThresholdChanged { from: u8, to: u8 },
+}
+
+/// A recorded mutation of a `MatrixBuilder` together with its side effects and
+/// the state of the builder before and after the mutation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixBuilderHistoryEntry {
+    pub mutation: MatrixBuilderMutation,
+    pub side_effects: Vec<MatrixBuilderSideEffect>,
+    before: MatrixBuilder,
+    after: MatrixBuilder,
+}
+
+impl MatrixBuilderHistoryEntry {
+    fn new(mutation: MatrixBuilderMutation, before: MatrixBuilder, after: MatrixBuilder) -> Self {
+        let side_effects = Self::side_effects(&mutation, &before, &after);
+        Self {
+            mutation,
+            side_effects,
+            before,
+            after,
+        }
+    }
+
+    fn side_effects(
+        mutation: &MatrixBuilderMutation,
+        before: &MatrixBuilder,
+        after: &MatrixBuilder,
+    ) -> Vec<MatrixBuilderSideEffect> {
+        let mut side_effects = Vec::new();
+
+        if let MatrixBuilderMutation::RemoveFactor(factor_source_id) = mutation {
+            let removed = |role: RoleKind, list: FactorListKind, from: &Vec<FactorSourceID>| {
+                from.contains(factor_source_id).then_some(
+                    MatrixBuilderSideEffect::FactorSourceRemovedFromRole {
+                        role,
+                        list,
+                        factor_source_id: *factor_source_id,
+                    },
+                )
+            };
+            side_effects.extend(
+                [
+                    removed(
+                        RoleKind::Primary,
+                        FactorListKind::Threshold,
+                        before.get_primary_threshold_factors(),
+                    ),
+                    removed(
+                        RoleKind::Primary,
+                        FactorListKind::Override,
+                        before.get_primary_override_factors(),
+                    ),
+                    removed(
+                        RoleKind::Recovery,
+                        FactorListKind::Override,
+                        before.get_recovery_factors(),
+                    ),
+                    removed(
+                        RoleKind::Confirmation,
+                        FactorListKind::Override,
+                        before.get_confirmation_factors(),
+                    ),
+                ]
+                .into_iter()
+                .flatten(),
+            );
+        }
+
+        let is_set_threshold = matches!(mutation, MatrixBuilderMutation::SetThreshold(_));
+        if !is_set_threshold && before.get_threshold() != after.get_threshold() {
+            side_effects.push(MatrixBuilderSideEffect::PrimaryThresholdChanged {
+                from: before.get_threshold(),
+                to: after.get_threshold(),
+            });
+        }
+
+        side_effects
+    }
+}
+
+/// An undo/redo history of mutations of a `MatrixBuilder`.
+///
+/// Mutations which did not change the builder, e.g. adding a factor source
+/// which is `ForeverInvalid`, are not recorded. Recording a new mutation clears
+/// the redo stack.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatrixBuilderHistory {
+    undo_stack: Vec<MatrixBuilderHistoryEntry>,
+    redo_stack: Vec<MatrixBuilderHistoryEntry>,
+}
+
+impl MatrixBuilderHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Performs `mutation` on `builder` and records it, returning the result of
+    /// the mutation.
+    pub fn mutate(
+        &mut self,
+        builder: &mut MatrixBuilder,
+        mutation: MatrixBuilderMutation,
+    ) -> MatrixBuilderMutateResult {
+        let before = builder.clone();
+        let result = builder.mutate(mutation);
+        if *builder != before {
+            self.undo_stack.push(MatrixBuilderHistoryEntry::new(
+                mutation,
+                before,
+                builder.clone(),
+            ));
+            self.redo_stack.clear();
+        }
+        result
+    }
+
+    /// Reverts `builder` to the state before the last recorded mutation,
+    /// including its side effects, returning the undone entry, if any.
+    pub fn undo(&mut self, builder: &mut MatrixBuilder) -> Option<&MatrixBuilderHistoryEntry> {
+        let entry = self.undo_stack.pop()?;
+        *builder = entry.before.clone();
+        self.redo_stack.push(entry);
+        self.redo_stack.last()
+    }
+
+    /// Reapplies the last undone mutation on `builder`, returning the redone
+    /// entry, if any.
+    pub fn redo(&mut self, builder: &mut MatrixBuilder) -> Option<&MatrixBuilderHistoryEntry> {
+        let entry = self.redo_stack.pop()?;
+        *builder = entry.after.clone();
+        self.undo_stack.push(entry);
+        self.undo_stack.last()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// The recorded entries which can be undone, oldest first.
+    pub fn undo_entries(&self) -> &Vec<MatrixBuilderHistoryEntry> {
+        &self.undo_stack
+    }
+
+    /// The undone entries which can be redone, most recently undone last.
+    pub fn redo_entries(&self) -> &Vec<MatrixBuilderHistoryEntry> {
+        &self.redo_stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::upper_case_acronyms)]
+    type SUT = MatrixBuilderHistory;
+
+    use MatrixBuilderMutation::*;
+
+    #[test]
+    fn empty() {
+        let mut sut = SUT::new();
+        let mut builder = MatrixBuilder::new();
+        assert!(!sut.can_undo());
+        assert!(!sut.can_redo());
+        assert!(sut.undo(&mut builder).is_none());
+        assert!(sut.redo(&mut builder).is_none());
+        assert_eq!(builder, MatrixBuilder::new());
+    }
+
+    #[test]
+    fn undo_redo_add() {
+        let mut sut = SUT::new();
+        let mut builder = MatrixBuilder::new();
+        sut.mutate(
+            &mut builder,
+            AddFactorSourceToRecoveryOverride(FactorSourceID::sample_ledger()),
+        )
+        .unwrap();
+        let after = builder.clone();
+
+        let undone = sut.undo(&mut builder).unwrap();
+        assert_eq!(
+            undone.mutation,
+            AddFactorSourceToRecoveryOverride(FactorSourceID::sample_ledger())
+        );
+        assert!(undone.side_effects.is_empty());
+        assert_eq!(builder, MatrixBuilder::new());
+        assert!(sut.can_redo());
+        assert!(!sut.can_undo());
+
+        sut.redo(&mut builder).unwrap();
+        assert_eq!(builder, after);
+        assert!(!sut.can_redo());
+    }
+
+    #[test]
+    fn forever_invalid_is_not_recorded() {
+        let mut sut = SUT::new();
+        let mut builder = MatrixBuilder::new();
+        let res = sut.mutate(
+            &mut builder,
+            AddFactorSourceToPrimaryOverride(FactorSourceID::sample_password()),
+        );
+        assert!(res.is_err());
+        assert!(!sut.can_undo());
+    }
+
+    #[test]
+    fn not_yet_valid_is_recorded() {
+        let mut sut = SUT::new();
+        let mut builder = MatrixBuilder::new();
+        let res = sut.mutate(&mut builder, SetThreshold(1));
+        assert!(res.is_err());
+        assert!(sut.can_undo());
+        sut.undo(&mut builder).unwrap();
+        assert_eq!(builder.get_threshold(), 0);
+    }
+
+    #[test]
+    fn new_mutation_clears_redo() {
+        let mut sut = SUT::new();
+        let mut builder = MatrixBuilder::new();
+        sut.mutate(&mut builder, SetNumberOfDaysUntilAutoConfirm(42))
+            .unwrap();
+        sut.undo(&mut builder).unwrap();
+        assert!(sut.can_redo());
+        sut.mutate(&mut builder, SetNumberOfDaysUntilAutoConfirm(7))
+            .unwrap();
+        assert!(!sut.can_redo());
+        assert_eq!(builder.get_number_of_days_until_auto_confirm(), 7);
+    }
+
+    #[test]
+    fn undo_remove_restores_lowered_threshold() {
+        let mut sut = SUT::new();
+        let mut builder = MatrixBuilder::new();
+        sut.mutate(
+            &mut builder,
+            AddFactorSourceToPrimaryThreshold(FactorSourceID::sample_device()),
+        )
+        .unwrap();
+        sut.mutate(
+            &mut builder,
+            AddFactorSourceToPrimaryThreshold(FactorSourceID::sample_ledger()),
+        )
+        .unwrap();
+        sut.mutate(&mut builder, SetThreshold(2)).unwrap();
+        sut.mutate(
+            &mut builder,
+            AddFactorSourceToRecoveryOverride(FactorSourceID::sample_ledger()),
+        )
+        .unwrap();
+        let before_remove = builder.clone();
+
+        sut.mutate(&mut builder, RemoveFactor(FactorSourceID::sample_ledger()))
+            .unwrap();
+        assert_eq!(builder.get_threshold(), 1);
+
+        let undone = sut.undo(&mut builder).unwrap();
+        pretty_assertions::assert_eq!(
+            undone.side_effects,
+            vec![
+                MatrixBuilderSideEffect::FactorSourceRemovedFromRole {
+                    role: RoleKind::Primary,
+                    list: FactorListKind::Threshold,
+                    factor_source_id: FactorSourceID::sample_ledger(),
+                },
+                MatrixBuilderSideEffect::FactorSourceRemovedFromRole {
+                    role: RoleKind::Recovery,
+                    list: FactorListKind::Override,
+                    factor_source_id: FactorSourceID::sample_ledger(),
+                },
+                MatrixBuilderSideEffect::PrimaryThresholdChanged { from: 2, to: 1 },
+            ]
+        );
+        assert_eq!(builder, before_remove);
+        assert_eq!(builder.get_threshold(), 2);
+    }
+}
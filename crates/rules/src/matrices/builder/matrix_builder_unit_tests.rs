@@ -347,6 +347,189 @@ fn add_factor_to_confirmation_then_same_to_primary_threshold_is_not_yet_valid()
         )));
 }
 
+mod with_matrix {
+    use super::*;
+
+    #[test]
+    fn build_roundtrip_all_configs() {
+        for matrix in [
+            MatrixOfFactorSourceIds::sample_config_11(),
+            MatrixOfFactorSourceIds::sample_config_12(),
+            MatrixOfFactorSourceIds::sample_config_13(),
+            MatrixOfFactorSourceIds::sample_config_14(),
+            MatrixOfFactorSourceIds::sample_config_15(),
+            MatrixOfFactorSourceIds::sample_config_21(),
+            MatrixOfFactorSourceIds::sample_config_22(),
+            MatrixOfFactorSourceIds::sample_config_23(),
+            MatrixOfFactorSourceIds::sample_config_24(),
+            MatrixOfFactorSourceIds::sample_config_30(),
+            MatrixOfFactorSourceIds::sample_config_40(),
+            MatrixOfFactorSourceIds::sample_config_51(),
+            MatrixOfFactorSourceIds::sample_config_52(),
+            MatrixOfFactorSourceIds::sample_config_60(),
+            MatrixOfFactorSourceIds::sample_config_70(),
+            MatrixOfFactorSourceIds::sample_config_80(),
+            MatrixOfFactorSourceIds::sample_config_90(),
+        ] {
+            let sut = SUT::with_matrix(matrix.clone());
+            assert_eq!(sut.build().unwrap(), matrix);
+        }
+    }
+
+    #[test]
+    fn keeps_number_of_days_until_auto_confirm() {
+        let matrix = MatrixOfFactorSourceIds::with_roles_and_days(
+            RoleWithFactorSourceIds::primary_with_factors(1, [FactorSourceID::sample_device()], []),
+            RoleWithFactorSourceIds::recovery_with_factors([FactorSourceID::sample_ledger()]),
+            RoleWithFactorSourceIds::confirmation_with_factors([FactorSourceID::sample_password()]),
+            42,
+        );
+        let sut = SUT::with_matrix(matrix);
+        assert_eq!(sut.get_number_of_days_until_auto_confirm(), 42);
+        assert_eq!(sut.get_threshold(), 1);
+    }
+
+    #[test]
+    fn edit() {
+        let mut sut = SUT::with_matrix(MatrixOfFactorSourceIds::sample_config_11());
+        sut.remove_factor(&FactorSourceID::sample_password())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_arculus())
+            .unwrap();
+        let built = sut.build().unwrap();
+        assert_eq!(
+            built.confirmation().get_override_factors(),
+            &vec![FactorSourceID::sample_arculus()]
+        );
+        assert_eq!(
+            built.primary(),
+            MatrixOfFactorSourceIds::sample_config_11().primary()
+        );
+    }
+}
+
+mod validation_report {
+    use super::*;
+
+    #[test]
+    fn empty_reports_each_role() {
+        let sut = make();
+        let report = sut.validation_report();
+        assert!(!report.is_valid());
+        pretty_assertions::assert_eq!(
+            report
+                .role_in_isolation_violations()
+                .iter()
+                .map(|v| v.validation)
+                .collect_vec(),
+            [
+                RoleKind::Primary,
+                RoleKind::Recovery,
+                RoleKind::Confirmation
+            ]
+            .into_iter()
+            .map(|role| MatrixBuilderValidation::RoleInIsolation {
+                role,
+                violation: RoleBuilderValidation::NotYetValid(
+                    NotYetValidReason::RoleMustHaveAtLeastOneFactor
+                )
+            })
+            .collect_vec()
+        );
+        assert!(report.combination_violations().is_empty());
+        assert_eq!(report.first_violation(), sut.validate());
+    }
+
+    #[test]
+    fn valid_is_empty() {
+        let mut sut = make();
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.set_threshold(1).unwrap();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_password())
+            .unwrap();
+
+        let report = sut.validation_report();
+        assert!(report.is_valid());
+        assert!(report.violations().is_empty());
+        assert_eq!(report.first_violation(), Ok(()));
+    }
+
+    #[test]
+    fn reports_all_combination_violations() {
+        let mut sut = make();
+        let fs = FactorSourceID::sample_ledger();
+        sut.add_factor_source_to_primary_override(fs).unwrap();
+        sut.add_factor_source_to_recovery_override(fs).unwrap();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_arculus())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_arculus())
+            .unwrap();
+        sut.number_of_days_until_auto_confirm = 0; // bypass validation
+
+        let report = sut.validation_report();
+        assert!(report.role_in_isolation_violations().is_empty());
+        pretty_assertions::assert_eq!(
+            report.combination_violations().clone(),
+            vec![
+                MatrixBuilderViolation::combination(
+                    [RoleKind::Primary, RoleKind::Recovery],
+                    [fs],
+                    MatrixRolesInCombinationNotYetValid::SingleFactorUsedInPrimaryMustNotBeUsedInAnyOtherRole
+                ),
+                MatrixBuilderViolation::combination(
+                    [RoleKind::Recovery, RoleKind::Confirmation],
+                    [FactorSourceID::sample_arculus()],
+                    MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap
+                ),
+                MatrixBuilderViolation::combination(
+                    [],
+                    [],
+                    MatrixRolesInCombinationBasicViolation::NumberOfDaysUntilAutoConfirmMustBeGreaterThanZero
+                ),
+            ]
+        );
+        assert_eq!(report.violations_of_role(RoleKind::Confirmation).len(), 1);
+        assert_eq!(report.first_violation(), sut.validate());
+    }
+
+    #[test]
+    fn reports_role_and_combination_violations() {
+        let mut sut = make();
+        sut.add_factor_source_to_primary_threshold(FactorSourceID::sample_device())
+            .unwrap();
+        sut.set_threshold(2).unwrap_err();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_ledger())
+            .unwrap();
+
+        let report = sut.validation_report();
+        pretty_assertions::assert_eq!(
+            report.violations().into_iter().cloned().collect_vec(),
+            vec![
+                MatrixBuilderViolation::role_in_isolation(
+                    RoleKind::Primary,
+                    RoleBuilderViolation::new(
+                        RoleBuilderValidation::NotYetValid(
+                            NotYetValidReason::ThresholdHigherThanThresholdFactorsLen
+                        ),
+                        [FactorSourceID::sample_device()]
+                    )
+                ),
+                MatrixBuilderViolation::combination(
+                    [RoleKind::Recovery, RoleKind::Confirmation],
+                    [FactorSourceID::sample_ledger()],
+                    MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap
+                ),
+            ]
+        );
+        assert_eq!(report.first_violation(), sut.validate());
+    }
+}
+
 mod remove {
     use super::*;
 
@@ -774,6 +957,174 @@ mod validation_for_addition_of_factor_source_for_each {
     }
 }
 
+mod validation_in_combination_for_addition_of_factor_source_for_each {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let sut = make();
+        let xs = sut
+            .validation_in_combination_for_addition_of_factor_source_to_recovery_override_for_each(
+                &IndexSet::new(),
+            );
+        assert_eq!(xs, IndexSet::new());
+    }
+
+    #[test]
+    fn factor_in_confirmation_is_forever_invalid_for_recovery() {
+        let mut sut = make();
+        sut.add_factor_source_to_confirmation_override(FactorSourceID::sample_ledger())
+            .unwrap();
+
+        // Role in isolation says Ok...
+        let xs = sut.validation_for_addition_of_factor_source_to_recovery_override_for_each(
+            &IndexSet::from_iter([FactorSourceID::sample_ledger()]),
+        );
+        assert_eq!(xs.first().unwrap().validation, Ok(()));
+
+        // ... but in combination it is not
+        let xs = sut
+            .validation_in_combination_for_addition_of_factor_source_to_recovery_override_for_each(
+                &IndexSet::from_iter([
+                    FactorSourceID::sample_ledger(),
+                    FactorSourceID::sample_arculus(),
+                ]),
+            );
+        pretty_assertions::assert_eq!(
+            xs.into_iter().collect_vec(),
+            vec![
+                FactorSourceInMatrixBuilderValidationStatus::new(
+                    RoleKind::Recovery,
+                    FactorSourceID::sample_ledger(),
+                    Err(MatrixBuilderValidation::CombinationViolation(
+                        MatrixRolesInCombinationViolation::ForeverInvalid(
+                            MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap
+                        )
+                    ))
+                ),
+                FactorSourceInMatrixBuilderValidationStatus::new(
+                    RoleKind::Recovery,
+                    FactorSourceID::sample_arculus(),
+                    Ok(())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn factor_in_recovery_is_forever_invalid_for_confirmation() {
+        let mut sut = make();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+
+        let xs = sut
+            .validation_in_combination_for_addition_of_factor_source_to_confirmation_override_for_each(
+                &IndexSet::from_iter([FactorSourceID::sample_ledger()]),
+            );
+        assert_eq!(
+            xs.first().unwrap().validation,
+            Err(MatrixBuilderValidation::CombinationViolation(
+                MatrixRolesInCombinationViolation::ForeverInvalid(
+                    MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn single_primary_factor_is_not_yet_valid_for_recovery() {
+        let mut sut = make();
+        sut.add_factor_source_to_primary_override(FactorSourceID::sample_ledger())
+            .unwrap();
+
+        let xs = sut
+            .validation_in_combination_for_addition_of_factor_source_to_recovery_override_for_each(
+                &IndexSet::from_iter([FactorSourceID::sample_ledger()]),
+            );
+        assert_eq!(
+            xs.first().unwrap().validation,
+            Err(MatrixBuilderValidation::CombinationViolation(
+                MatrixRolesInCombinationViolation::NotYetValid(
+                    MatrixRolesInCombinationNotYetValid::SingleFactorUsedInPrimaryMustNotBeUsedInAnyOtherRole
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn factor_in_recovery_is_not_yet_valid_for_empty_primary() {
+        let mut sut = make();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_ledger())
+            .unwrap();
+
+        let xs = sut
+            .validation_in_combination_for_addition_of_factor_source_to_primary_threshold_for_each(
+                &IndexSet::from_iter([FactorSourceID::sample_ledger()]),
+            );
+        assert_eq!(
+            xs.first().unwrap().validation,
+            Err(MatrixBuilderValidation::CombinationViolation(
+                MatrixRolesInCombinationViolation::NotYetValid(
+                    MatrixRolesInCombinationNotYetValid::SingleFactorUsedInPrimaryMustNotBeUsedInAnyOtherRole
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn role_forever_invalid_takes_precedence() {
+        let mut sut = make();
+        sut.add_factor_source_to_recovery_override(FactorSourceID::sample_device())
+            .unwrap();
+        sut.add_factor_source_to_primary_override(FactorSourceID::sample_device_other())
+            .unwrap();
+
+        let xs = sut
+            .validation_in_combination_for_addition_of_factor_source_to_primary_override_for_each(
+                &IndexSet::from_iter([
+                    FactorSourceID::sample_device(),
+                    FactorSourceID::sample_password(),
+                ]),
+            );
+        pretty_assertions::assert_eq!(
+            xs.into_iter().collect_vec(),
+            vec![
+                FactorSourceInMatrixBuilderValidationStatus::new(
+                    RoleKind::Primary,
+                    FactorSourceID::sample_device(),
+                    Err(MatrixBuilderValidation::RoleInIsolation {
+                        role: RoleKind::Primary,
+                        violation: RoleBuilderValidation::ForeverInvalid(
+                            ForeverInvalidReason::PrimaryCannotHaveMultipleDevices
+                        )
+                    })
+                ),
+                FactorSourceInMatrixBuilderValidationStatus::new(
+                    RoleKind::Primary,
+                    FactorSourceID::sample_password(),
+                    Err(MatrixBuilderValidation::RoleInIsolation {
+                        role: RoleKind::Primary,
+                        violation: RoleBuilderValidation::ForeverInvalid(
+                            ForeverInvalidReason::PrimaryCannotHavePasswordInOverrideList
+                        )
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn does_not_mutate() {
+        let sut = make();
+        let before = sut.clone();
+        let _ = sut
+            .validation_in_combination_for_addition_of_factor_source_to_primary_threshold_for_each(
+                &IndexSet::from_iter([FactorSourceID::sample_device()]),
+            );
+        assert_eq!(sut, before);
+    }
+}
+
 mod validation_of_addition_of_kind {
     use super::*;
 
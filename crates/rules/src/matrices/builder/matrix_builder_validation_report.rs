@@ -0,0 +1,103 @@
+use crate::prelude::*;
+
+/// A single violation found when validating a `MatrixBuilder`, tagged with the
+/// roles it concerns and the FactorSourceIDs causing it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatrixBuilderViolation {
+    /// For a role in isolation violation this is the single role violating
+    /// its rules, for a combination violation it is every role involved, which
+    /// might be none, e.g. for invalid number of days until auto confirm.
+    pub roles: Vec<RoleKind>,
+
+    /// The FactorSourceIDs causing the violation, might be empty, e.g. if a
+    /// role does not have any factors at all.
+    pub factor_source_ids: Vec<FactorSourceID>,
+
+    pub validation: MatrixBuilderValidation,
+}
+
+impl MatrixBuilderViolation {
+    pub(crate) fn role_in_isolation(role: RoleKind, violation: RoleBuilderViolation) -> Self {
+        Self {
+            roles: vec![role],
+            factor_source_ids: violation.factor_source_ids,
+            validation: MatrixBuilderValidation::RoleInIsolation {
+                role,
+                violation: violation.violation,
+            },
+        }
+    }
+
+    pub(crate) fn combination(
+        roles: impl IntoIterator<Item = RoleKind>,
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+        violation: impl Into<MatrixRolesInCombinationViolation>,
+    ) -> Self {
+        Self {
+            roles: roles.into_iter().collect(),
+            factor_source_ids: factor_source_ids.into_iter().collect(),
+            validation: MatrixBuilderValidation::CombinationViolation(violation.into()),
+        }
+    }
+}
+
+/// Every violation of a `MatrixBuilder`, both of each role in isolation and of
+/// the roles in combination, as opposed to `MatrixBuilder::validate` which
+/// only returns the first one found.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MatrixBuilderValidationReport {
+    role_in_isolation_violations: Vec<MatrixBuilderViolation>,
+    combination_violations: Vec<MatrixBuilderViolation>,
+}
+
+impl MatrixBuilderValidationReport {
+    pub(crate) fn new(
+        role_in_isolation_violations: impl IntoIterator<Item = MatrixBuilderViolation>,
+        combination_violations: impl IntoIterator<Item = MatrixBuilderViolation>,
+    ) -> Self {
+        Self {
+            role_in_isolation_violations: role_in_isolation_violations.into_iter().collect(),
+            combination_violations: combination_violations.into_iter().collect(),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.role_in_isolation_violations.is_empty() && self.combination_violations.is_empty()
+    }
+
+    /// Violations of the rules of each role in isolation, primary first, then
+    /// recovery and lastly confirmation.
+    pub fn role_in_isolation_violations(&self) -> &Vec<MatrixBuilderViolation> {
+        &self.role_in_isolation_violations
+    }
+
+    /// Violations of the rules of the roles in combination.
+    pub fn combination_violations(&self) -> &Vec<MatrixBuilderViolation> {
+        &self.combination_violations
+    }
+
+    /// All violations, role in isolation violations first, followed by the
+    /// combination violations.
+    pub fn violations(&self) -> Vec<&MatrixBuilderViolation> {
+        self.role_in_isolation_violations
+            .iter()
+            .chain(self.combination_violations.iter())
+            .collect()
+    }
+
+    /// All violations concerning `role`.
+    pub fn violations_of_role(&self, role: RoleKind) -> Vec<&MatrixBuilderViolation> {
+        self.violations()
+            .into_iter()
+            .filter(|v| v.roles.contains(&role))
+            .collect()
+    }
+
+    /// The first violation if any, i.e. the same result `MatrixBuilder::validate`
+    /// would return.
+    pub fn first_violation(&self) -> MatrixBuilderMutateResult {
+        self.violations()
+            .first()
+            .map_or(Ok(()), |v| Err(v.validation))
+    }
+}
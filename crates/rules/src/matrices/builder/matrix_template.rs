@@ -1,26 +1,52 @@
 use crate::prelude::*;
 
 /// A Matrix of FactorSourceTemplates, can be used to create template
-/// "SecurityShields", mostly useful for coding/tests, but theoretically
-/// we could UniFFI export these and use them in the hosts wallets, which would
-/// pre-populate SecurityShield-builder flow screens - if hosts/Sargon manages
-/// to assign each template "slot" with a concrete FactorSourceID, known as
-/// materialization.
+/// "SecurityShields". The presets, see `MatrixTemplatePreset`, are UniFFI
+/// exported for use in the hosts wallets, to pre-populate SecurityShield-builder
+/// flow screens, by assigning each template "slot" with a concrete
+/// FactorSourceID, known as materialization.
 pub type MatrixTemplate = AbstractMatrixBuilt<FactorSourceTemplate>;
 
+impl IsValidatedOnDeserialization for MatrixTemplate {
+    type Error = MatrixBuilderValidation;
+
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        self.validate()
+    }
+}
+
+impl MatrixTemplate {
+    /// Validates the structure of this template, i.e. that it materializes into
+    /// a valid MatrixOfFactorSourceIds, by assigning each template slot a
+    /// distinct placeholder FactorSourceID.
+    pub fn validate(&self) -> MatrixBuilderMutateResult {
+        MatrixOfFactorSourceIds {
+            built: PhantomData,
+            primary_role: self.primary_role.with_placeholder_factor_source_ids(),
+            recovery_role: self.recovery_role.with_placeholder_factor_source_ids(),
+            confirmation_role: self.confirmation_role.with_placeholder_factor_source_ids(),
+            number_of_days_until_auto_confirm: self.number_of_days_until_auto_confirm,
+        }
+        .validate()
+    }
+}
+
 impl<const R: u8> AbstractBuiltRoleWithFactor<R, FactorSourceTemplate> {
     /// Tries to materialize a RoleWithFactorSourceIds from a RoleTemplate by
     /// assigning each template with a concrete FactorSourceID using the FactorSourceIdAssigner.
     pub(crate) fn assign(
         self,
         factor_source_id_assigner: &mut FactorSourceIdAssigner,
-    ) -> Result<RoleWithFactorSourceIds<R>, CommonError> {
-        let mut fulfill =
-            |xs: &Vec<FactorSourceTemplate>| -> Result<Vec<FactorSourceID>, CommonError> {
-                xs.iter()
-                    .map(|f| factor_source_id_assigner.next(f))
-                    .collect::<Result<Vec<_>, CommonError>>()
-            };
+    ) -> Result<RoleWithFactorSourceIds<R>, MatrixTemplateMaterializationError> {
+        let role = self.role();
+        let mut fulfill = |xs: &Vec<FactorSourceTemplate>| -> Result<
+            Vec<FactorSourceID>,
+            MatrixTemplateMaterializationError,
+        > {
+            xs.iter()
+                .map(|f| factor_source_id_assigner.next(f, role))
+                .collect::<Result<Vec<_>, MatrixTemplateMaterializationError>>()
+        };
         Ok(RoleWithFactorSourceIds::with_factors(
             self.get_threshold(),
             fulfill(self.get_threshold_factors())?,
@@ -34,17 +60,54 @@ impl<const R: u8> AbstractBuiltRoleWithFactor<R, FactorSourceTemplate> {
 pub(crate) struct FactorSourceIdAssigner {
     factor_source_ids: Vec<FactorSourceID>,
     map: IndexMap<FactorSourceTemplate, FactorSourceID>,
+
+    /// The number of distinct FactorSourceIDs of each kind supplied.
+    supplied: IndexMap<FactorSourceKind, usize>,
+
+    /// The number of distinct FactorSourceIDs of each kind the template needs.
+    needed: IndexMap<FactorSourceKind, usize>,
 }
 
 impl FactorSourceIdAssigner {
-    fn new(factor_source_ids: impl IntoIterator<Item = FactorSourceID>) -> Self {
+    fn new(
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+        needed: IndexMap<FactorSourceKind, usize>,
+    ) -> Self {
+        Self::with_assignments(IndexMap::new(), factor_source_ids, needed)
+    }
+
+    /// Uses the explicit `assignments` for their template slots, and the
+    /// `factor_source_ids` not explicitly assigned for the remaining slots.
+    fn with_assignments(
+        assignments: IndexMap<FactorSourceTemplate, FactorSourceID>,
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+        needed: IndexMap<FactorSourceKind, usize>,
+    ) -> Self {
+        let assigned = assignments.values().cloned().collect::<IndexSet<_>>();
+        let factor_source_ids = factor_source_ids
+            .into_iter()
+            .unique()
+            .filter(|f| !assigned.contains(f))
+            .collect_vec();
+        let supplied = assigned
+            .iter()
+            .chain(factor_source_ids.iter())
+            .counts_by(|f| f.get_factor_source_kind())
+            .into_iter()
+            .collect();
         Self {
-            factor_source_ids: factor_source_ids.into_iter().collect_vec(),
-            map: IndexMap::new(),
+            factor_source_ids,
+            map: assignments,
+            supplied,
+            needed,
         }
     }
 
-    fn next(&mut self, template: &FactorSourceTemplate) -> Result<FactorSourceID, CommonError> {
+    fn next(
+        &mut self,
+        template: &FactorSourceTemplate,
+        role: RoleKind,
+    ) -> Result<FactorSourceID, MatrixTemplateMaterializationError> {
         if let Some(existing) = self.map.get(template) {
             Ok(*existing)
         } else if let Some(index_of_next) = self
@@ -56,7 +119,16 @@ impl FactorSourceIdAssigner {
             self.map.insert(template.clone(), next);
             Ok(next)
         } else {
-            Err(CommonError::Unknown)
+            Err(MatrixTemplateMaterializationError {
+                template: template.clone(),
+                role,
+                supplied: self
+                    .supplied
+                    .get(&template.kind)
+                    .cloned()
+                    .unwrap_or_default(),
+                needed: self.needed.get(&template.kind).cloned().unwrap_or_default(),
+            })
         }
     }
 }
@@ -64,12 +136,88 @@ impl FactorSourceIdAssigner {
 impl MatrixTemplate {
     /// Tries to materialize a MatrixOfFactorSourceIds from a MatrixTemplate by
     /// assigning each template with a concrete FactorSourceID using the `factor_source_ids`.`
+    ///
+    /// # Throws
+    /// If there are not enough `factor_source_ids` of the kind of some template
+    /// slot, naming the first slot which could not be assigned.
     pub fn materialize(
         self,
         factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
-    ) -> Result<MatrixOfFactorSourceIds, CommonError> {
+    ) -> Result<MatrixOfFactorSourceIds, MatrixTemplateMaterializationError> {
+        let assigner = FactorSourceIdAssigner::new(
+            factor_source_ids,
+            self.number_of_factor_sources_needed_per_kind(),
+        );
+        self.materialize_using(assigner)
+    }
+
+    /// Tries to materialize a MatrixOfFactorSourceIds from a MatrixTemplate by
+    /// assigning the template slots in `assignments` with their explicitly chosen
+    /// FactorSourceID, and each remaining slot with the first FactorSourceID
+    /// of matching kind in `factor_source_ids`, i.e. `factor_source_ids` is the
+    /// preference order used for auto-filling.
+    ///
+    /// FactorSourceIDs in `assignments` are never used to fill any other slot.
+    ///
+    /// # Throws
+    /// If any assignment is invalid, i.e. of a slot not in this template, of a
+    /// FactorSourceID of another kind than the slot, or of the same FactorSourceID
+    /// to multiple slots, or if any remaining slot cannot be assigned.
+    pub fn materialize_with_assignments(
+        self,
+        assignments: IndexMap<FactorSourceTemplate, FactorSourceID>,
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+    ) -> Result<MatrixOfFactorSourceIds, MatrixTemplateAssignmentError> {
+        let slots = self.factor_source_templates();
+        for (template, factor_source_id) in assignments.iter() {
+            if !slots.contains(template) {
+                return Err(MatrixTemplateAssignmentError::SlotNotInTemplate {
+                    template: template.clone(),
+                });
+            }
+            if factor_source_id.get_factor_source_kind() != template.kind {
+                return Err(MatrixTemplateAssignmentError::FactorSourceKindMismatch {
+                    template: template.clone(),
+                    factor_source_id: *factor_source_id,
+                });
+            }
+        }
+        if let Some(factor_source_id) = assignments.values().duplicates().next() {
+            return Err(
+                MatrixTemplateAssignmentError::FactorSourceAssignedToMultipleSlots {
+                    factor_source_id: *factor_source_id,
+                },
+            );
+        }
+
+        let assigner = FactorSourceIdAssigner::with_assignments(
+            assignments,
+            factor_source_ids,
+            self.number_of_factor_sources_needed_per_kind(),
+        );
+        self.materialize_using(assigner).map_err(Into::into)
+    }
+
+    /// All distinct template slots, primary threshold first, then primary override,
+    /// recovery and lastly confirmation.
+    pub(crate) fn factor_source_templates(&self) -> IndexSet<FactorSourceTemplate> {
+        [
+            self.primary().get_threshold_factors(),
+            self.primary().get_override_factors(),
+            self.recovery().get_override_factors(),
+            self.confirmation().get_override_factors(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect()
+    }
+
+    fn materialize_using(
+        self,
+        mut assigner: FactorSourceIdAssigner,
+    ) -> Result<MatrixOfFactorSourceIds, MatrixTemplateMaterializationError> {
         let number_of_days_until_auto_confirm = self.number_of_days_until_auto_confirm;
-        let mut assigner = FactorSourceIdAssigner::new(factor_source_ids);
         let primary_role = self.primary_role.assign(&mut assigner)?;
         let recovery_role = self.recovery_role.assign(&mut assigner)?;
         let confirmation_role = self.confirmation_role.assign(&mut assigner)?;
@@ -84,6 +232,55 @@ impl MatrixTemplate {
     }
 }
 
+impl MatrixTemplate {
+    /// The inverse of `materialize`, abstracts `matrix` into a MatrixTemplate by
+    /// replacing each FactorSourceID with a FactorSourceTemplate of the same kind.
+    ///
+    /// Placeholder ids are assigned per kind in order of first appearance, primary
+    /// threshold first, then primary override, recovery and lastly confirmation,
+    /// and the same FactorSourceID is always replaced with the same template,
+    /// across all roles.
+    pub fn from_matrix_of_factor_source_ids(matrix: &MatrixOfFactorSourceIds) -> Self {
+        let mut templates = IndexMap::<FactorSourceID, FactorSourceTemplate>::new();
+        let mut abstracting = |xs: &Vec<FactorSourceID>| -> Vec<FactorSourceTemplate> {
+            xs.iter()
+                .map(|f| {
+                    let kind = f.get_factor_source_kind();
+                    let next_id = templates.values().filter(|t| t.kind == kind).count() as u8;
+                    templates
+                        .entry(*f)
+                        .or_insert_with(|| FactorSourceTemplate::new(kind, next_id))
+                        .clone()
+                })
+                .collect()
+        };
+
+        let primary_role = PrimaryRoleTemplate::with_factors(
+            matrix.primary().get_threshold(),
+            abstracting(matrix.primary().get_threshold_factors()),
+            abstracting(matrix.primary().get_override_factors()),
+        );
+        let recovery_role = RecoveryRoleTemplate::with_factors(
+            0,
+            [],
+            abstracting(matrix.recovery().get_override_factors()),
+        );
+        let confirmation_role = ConfirmationRoleTemplate::with_factors(
+            0,
+            [],
+            abstracting(matrix.confirmation().get_override_factors()),
+        );
+
+        Self {
+            built: PhantomData,
+            primary_role,
+            recovery_role,
+            confirmation_role,
+            number_of_days_until_auto_confirm: matrix.number_of_days_until_auto_confirm,
+        }
+    }
+}
+
 impl MatrixTemplate {
     fn new(
         primary_role: PrimaryRoleTemplate,
@@ -318,6 +515,372 @@ impl MatrixTemplate {
     }
 }
 
+#[cfg(test)]
+mod test_materialize_error {
+    use super::*;
+
+    #[test]
+    fn missing_password() {
+        let res = MatrixTemplate::config_11().materialize([
+            FactorSourceID::sample_device(),
+            FactorSourceID::sample_ledger(),
+        ]);
+        assert_eq!(
+            res,
+            Err(MatrixTemplateMaterializationError {
+                template: FactorSourceTemplate::password(),
+                role: RoleKind::Confirmation,
+                supplied: 0,
+                needed: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn too_few_ledgers() {
+        let res = MatrixTemplate::config_22().materialize([
+            FactorSourceID::sample_device(),
+            FactorSourceID::sample_ledger(),
+            FactorSourceID::sample_ledger(),
+        ]);
+        assert_eq!(
+            res,
+            Err(MatrixTemplateMaterializationError {
+                template: FactorSourceTemplate::ledger_other(),
+                role: RoleKind::Primary,
+                supplied: 1,
+                needed: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn display() {
+        let err = MatrixTemplate::config_60()
+            .materialize([FactorSourceID::sample_device()])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unable to assign a factor source to template slot TrustedContact #0 in role Recovery, supplied 0 factor source(s) of that kind but 1 needed"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_materialize_with_assignments {
+    use super::*;
+
+    #[allow(clippy::upper_case_acronyms)]
+    type SUT = MatrixTemplate;
+
+    #[test]
+    fn explicit_ledgers_are_swapped() {
+        let matrix = SUT::config_22()
+            .materialize_with_assignments(
+                IndexMap::from_iter([
+                    (
+                        FactorSourceTemplate::ledger(),
+                        FactorSourceID::sample_ledger_other(),
+                    ),
+                    (
+                        FactorSourceTemplate::ledger_other(),
+                        FactorSourceID::sample_ledger(),
+                    ),
+                ]),
+                [
+                    FactorSourceID::sample_ledger(),
+                    FactorSourceID::sample_ledger_other(),
+                    FactorSourceID::sample_device(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            matrix.primary().get_threshold_factors(),
+            &vec![
+                FactorSourceID::sample_ledger_other(),
+                FactorSourceID::sample_ledger()
+            ]
+        );
+        assert_eq!(
+            matrix.confirmation().get_override_factors(),
+            &vec![FactorSourceID::sample_device()]
+        );
+    }
+
+    #[test]
+    fn preference_order_fills_remaining() {
+        let matrix = SUT::config_24()
+            .materialize_with_assignments(
+                IndexMap::new(),
+                [
+                    FactorSourceID::sample_ledger_other(),
+                    FactorSourceID::sample_device(),
+                    FactorSourceID::sample_ledger(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            matrix.recovery().get_override_factors(),
+            &vec![FactorSourceID::sample_ledger_other()]
+        );
+        assert_eq!(
+            matrix.confirmation().get_override_factors(),
+            &vec![FactorSourceID::sample_ledger()]
+        );
+    }
+
+    #[test]
+    fn empty_assignments_is_same_as_materialize() {
+        assert_eq!(
+            SUT::config_30().materialize_with_assignments(
+                IndexMap::new(),
+                *ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD
+            ),
+            Ok(MatrixOfFactorSourceIds::sample_config_30())
+        );
+    }
+
+    #[test]
+    fn assigned_factor_is_not_used_for_other_slot() {
+        let res = SUT::config_22().materialize_with_assignments(
+            IndexMap::from_iter([(
+                FactorSourceTemplate::ledger(),
+                FactorSourceID::sample_ledger(),
+            )]),
+            [
+                FactorSourceID::sample_ledger(),
+                FactorSourceID::sample_device(),
+            ],
+        );
+        assert_eq!(
+            res,
+            Err(MatrixTemplateAssignmentError::Materialization(
+                MatrixTemplateMaterializationError {
+                    template: FactorSourceTemplate::ledger_other(),
+                    role: RoleKind::Primary,
+                    supplied: 1,
+                    needed: 2,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn err_kind_mismatch() {
+        let res = SUT::config_11().materialize_with_assignments(
+            IndexMap::from_iter([(
+                FactorSourceTemplate::ledger(),
+                FactorSourceID::sample_arculus(),
+            )]),
+            *ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD,
+        );
+        assert_eq!(
+            res,
+            Err(MatrixTemplateAssignmentError::FactorSourceKindMismatch {
+                template: FactorSourceTemplate::ledger(),
+                factor_source_id: FactorSourceID::sample_arculus(),
+            })
+        );
+    }
+
+    #[test]
+    fn err_slot_not_in_template() {
+        let res = SUT::config_11().materialize_with_assignments(
+            IndexMap::from_iter([(
+                FactorSourceTemplate::ledger_other(),
+                FactorSourceID::sample_ledger_other(),
+            )]),
+            *ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD,
+        );
+        assert_eq!(
+            res,
+            Err(MatrixTemplateAssignmentError::SlotNotInTemplate {
+                template: FactorSourceTemplate::ledger_other(),
+            })
+        );
+    }
+
+    #[test]
+    fn err_same_factor_assigned_to_multiple_slots() {
+        let res = SUT::config_22().materialize_with_assignments(
+            IndexMap::from_iter([
+                (
+                    FactorSourceTemplate::ledger(),
+                    FactorSourceID::sample_ledger(),
+                ),
+                (
+                    FactorSourceTemplate::ledger_other(),
+                    FactorSourceID::sample_ledger(),
+                ),
+            ]),
+            *ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD,
+        );
+        assert_eq!(
+            res,
+            Err(
+                MatrixTemplateAssignmentError::FactorSourceAssignedToMultipleSlots {
+                    factor_source_id: FactorSourceID::sample_ledger(),
+                }
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_from_matrix_of_factor_source_ids {
+    use super::*;
+
+    #[allow(clippy::upper_case_acronyms)]
+    type SUT = MatrixTemplate;
+
+    #[test]
+    fn presets_are_derived_from_their_samples() {
+        MatrixTemplatePreset::all().into_iter().for_each(|preset| {
+            let template = preset.template();
+            let matrix = template
+                .clone()
+                .materialize(*ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD)
+                .unwrap();
+            pretty_assertions::assert_eq!(SUT::from_matrix_of_factor_source_ids(&matrix), template);
+        });
+    }
+
+    #[test]
+    fn materialize_derived_template_is_original() {
+        let matrix = MatrixOfFactorSourceIds::sample_config_90();
+        let factor_source_ids = matrix
+            .primary()
+            .get_threshold_factors()
+            .iter()
+            .chain(matrix.primary().get_override_factors())
+            .chain(matrix.recovery().get_override_factors())
+            .chain(matrix.confirmation().get_override_factors())
+            .cloned()
+            .collect_vec();
+        let template = SUT::from_matrix_of_factor_source_ids(&matrix);
+        assert_eq!(template.materialize(factor_source_ids), Ok(matrix));
+    }
+
+    #[test]
+    fn apply_to_other_factor_sources() {
+        let matrix = MatrixOfFactorSourceIds::with_roles_and_days(
+            PrimaryRoleWithFactorSourceIds::primary_with_factors(
+                2,
+                [
+                    FactorSourceID::sample_device_other(),
+                    FactorSourceID::sample_arculus_other(),
+                ],
+                [],
+            ),
+            RecoveryRoleWithFactorSourceIds::recovery_with_factors([
+                FactorSourceID::sample_arculus_other(),
+                FactorSourceID::sample_arculus(),
+            ]),
+            ConfirmationRoleWithFactorSourceIds::confirmation_with_factors([
+                FactorSourceID::sample_device_other(),
+            ]),
+            42,
+        );
+        let template = SUT::from_matrix_of_factor_source_ids(&matrix);
+        assert_eq!(
+            template.recovery().get_override_factors(),
+            &vec![
+                FactorSourceTemplate::new(FactorSourceKind::ArculusCard, 0),
+                FactorSourceTemplate::new(FactorSourceKind::ArculusCard, 1),
+            ]
+        );
+        let applied = template
+            .materialize([
+                FactorSourceID::sample_arculus(),
+                FactorSourceID::sample_device(),
+                FactorSourceID::sample_arculus_other(),
+            ])
+            .unwrap();
+        assert_eq!(
+            applied,
+            MatrixOfFactorSourceIds::with_roles_and_days(
+                PrimaryRoleWithFactorSourceIds::primary_with_factors(
+                    2,
+                    [
+                        FactorSourceID::sample_device(),
+                        FactorSourceID::sample_arculus(),
+                    ],
+                    [],
+                ),
+                RecoveryRoleWithFactorSourceIds::recovery_with_factors([
+                    FactorSourceID::sample_arculus(),
+                    FactorSourceID::sample_arculus_other(),
+                ]),
+                ConfirmationRoleWithFactorSourceIds::confirmation_with_factors([
+                    FactorSourceID::sample_device(),
+                ]),
+                42,
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_validate {
+    use super::*;
+
+    #[test]
+    fn all_presets_are_valid() {
+        MatrixTemplatePreset::all()
+            .into_iter()
+            .for_each(|p| assert_eq!(p.template().validate(), Ok(())));
+    }
+
+    #[test]
+    fn password_in_primary_override_is_invalid() {
+        let template = MatrixTemplate::new(
+            PrimaryRoleTemplate::with_factors(
+                1,
+                [FactorSourceTemplate::device()],
+                [FactorSourceTemplate::password()],
+            ),
+            RecoveryRoleTemplate::new([FactorSourceTemplate::device()]),
+            ConfirmationRoleTemplate::new([FactorSourceTemplate::ledger()]),
+        );
+        assert_eq!(
+            template.validate(),
+            Err(MatrixBuilderValidation::RoleInIsolation {
+                role: RoleKind::Primary,
+                violation: RoleBuilderValidation::ForeverInvalid(
+                    ForeverInvalidReason::PrimaryCannotHavePasswordInOverrideList
+                )
+            })
+        );
+    }
+
+    #[test]
+    fn same_template_in_recovery_and_confirmation_is_invalid() {
+        let template = MatrixTemplate::new(
+            PrimaryRoleTemplate::new([FactorSourceTemplate::device()]),
+            RecoveryRoleTemplate::new([FactorSourceTemplate::ledger()]),
+            ConfirmationRoleTemplate::new([FactorSourceTemplate::ledger()]),
+        );
+        assert_eq!(
+            template.validate(),
+            Err(MatrixBuilderValidation::CombinationViolation(
+                MatrixRolesInCombinationViolation::ForeverInvalid(
+                    MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn distinct_templates_of_same_kind_do_not_overlap() {
+        let template = MatrixTemplate::new(
+            PrimaryRoleTemplate::new([FactorSourceTemplate::device()]),
+            RecoveryRoleTemplate::new([FactorSourceTemplate::ledger()]),
+            ConfirmationRoleTemplate::new([FactorSourceTemplate::ledger_other()]),
+        );
+        assert_eq!(template.validate(), Ok(()));
+    }
+}
+
 #[cfg(test)]
 mod test_templates {
     use super::*;
@@ -0,0 +1,330 @@
+use crate::prelude::*;
+
+/// The hardcoded `MatrixTemplate` presets, `config_11` ... `config_90`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatrixTemplatePreset {
+    Config11,
+    Config12,
+    Config13,
+    Config14,
+    Config15,
+    Config21,
+    Config22,
+    Config23,
+    Config24,
+    Config30,
+    Config40,
+    Config51,
+    Config52,
+    Config60,
+    Config70,
+    Config80,
+    Config90,
+}
+
+impl MatrixTemplatePreset {
+    /// All presets, in the order of their config number.
+    pub fn all() -> Vec<Self> {
+        use MatrixTemplatePreset::*;
+        vec![
+            Config11, Config12, Config13, Config14, Config15, Config21, Config22, Config23,
+            Config24, Config30, Config40, Config51, Config52, Config60, Config70, Config80,
+            Config90,
+        ]
+    }
+
+    pub fn template(&self) -> MatrixTemplate {
+        match self {
+            Self::Config11 => MatrixTemplate::config_11(),
+            Self::Config12 => MatrixTemplate::config_12(),
+            Self::Config13 => MatrixTemplate::config_13(),
+            Self::Config14 => MatrixTemplate::config_14(),
+            Self::Config15 => MatrixTemplate::config_15(),
+            Self::Config21 => MatrixTemplate::config_21(),
+            Self::Config22 => MatrixTemplate::config_22(),
+            Self::Config23 => MatrixTemplate::config_23(),
+            Self::Config24 => MatrixTemplate::config_24(),
+            Self::Config30 => MatrixTemplate::config_30(),
+            Self::Config40 => MatrixTemplate::config_40(),
+            Self::Config51 => MatrixTemplate::config_51(),
+            Self::Config52 => MatrixTemplate::config_52(),
+            Self::Config60 => MatrixTemplate::config_60(),
+            Self::Config70 => MatrixTemplate::config_70(),
+            Self::Config80 => MatrixTemplate::config_80(),
+            Self::Config90 => MatrixTemplate::config_90(),
+        }
+    }
+
+    /// The template of this preset with a human readable name and description,
+    /// e.g. for displaying the presets in hosts.
+    pub fn named(&self) -> NamedMatrixTemplate {
+        let (name, description) = match self {
+            Self::Config11 => (
+                "Device and Ledger",
+                "Sign with this phone and a Ledger, recover with either, confirm with a password.",
+            ),
+            Self::Config12 => (
+                "Ledger and password",
+                "Sign with a Ledger and a password, recover with this phone or the Ledger, confirm with a password.",
+            ),
+            Self::Config13 => (
+                "Device and password",
+                "Sign with this phone and a password, recover with this phone or a Ledger, confirm with a password.",
+            ),
+            Self::Config14 => (
+                "Device",
+                "Sign with this phone, recover with a Ledger, confirm with a password.",
+            ),
+            Self::Config15 => (
+                "Ledger",
+                "Sign with a Ledger, recover with this phone, confirm with a password.",
+            ),
+            Self::Config21 => (
+                "Device and Ledger, two Ledgers for recovery",
+                "Sign with this phone and a Ledger, recover with either of two Ledgers, confirm with this phone.",
+            ),
+            Self::Config22 => (
+                "Two Ledgers",
+                "Sign with two Ledgers, recover with either of them, confirm with this phone.",
+            ),
+            Self::Config23 => (
+                "Ledger, another Ledger for recovery",
+                "Sign with a Ledger, recover with another Ledger, confirm with this phone.",
+            ),
+            Self::Config24 => (
+                "Device, Ledgers for recovery and confirmation",
+                "Sign with this phone, recover with a Ledger, confirm with another Ledger.",
+            ),
+            Self::Config30 => (
+                "Device and Ledger, confirm with device or password",
+                "Sign with this phone and a Ledger, recover with either of two Ledgers, confirm with this phone or a password.",
+            ),
+            Self::Config40 => (
+                "Device and Ledger, passwords and mnemonic for confirmation",
+                "Sign with this phone and a Ledger, recover with either, confirm with one of two passwords or an off device mnemonic.",
+            ),
+            Self::Config51 => (
+                "Device and password, trusted contact",
+                "Sign with this phone and a password, recover with a trusted contact, confirm with a password.",
+            ),
+            Self::Config52 => (
+                "Device and password, two trusted contacts",
+                "Sign with this phone and a password, recover with either of two trusted contacts or this phone, confirm with one of two passwords or an off device mnemonic.",
+            ),
+            Self::Config60 => (
+                "Trusted contact",
+                "Sign with this phone, recover with a trusted contact, confirm with security questions.",
+            ),
+            Self::Config70 => (
+                "Device and Ledger, trusted contact",
+                "Sign with this phone and a Ledger, recover with a trusted contact or the Ledger, confirm with this phone.",
+            ),
+            Self::Config80 => (
+                "Device and Ledger, security questions",
+                "Sign with this phone and a Ledger, recover with either, confirm with security questions.",
+            ),
+            Self::Config90 => (
+                "Device and Ledger, trusted contact and security questions",
+                "Sign with this phone and a Ledger, recover with a trusted contact or this phone, confirm with security questions.",
+            ),
+        };
+        NamedMatrixTemplate::new(name, description, self.template())
+    }
+
+    /// For each preset, whether it can be materialized using `factor_source_ids`
+    /// or if not, which FactorSourceKinds are missing.
+    pub fn materializability_of_all(
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+    ) -> IndexMap<Self, MatrixTemplateMaterializability> {
+        let factor_source_ids = factor_source_ids.into_iter().collect_vec();
+        Self::all()
+            .into_iter()
+            .map(|preset| {
+                (
+                    preset,
+                    preset
+                        .template()
+                        .materializability(factor_source_ids.clone()),
+                )
+            })
+            .collect()
+    }
+
+    /// The presets which can be materialized using `factor_source_ids`.
+    pub fn materializable(
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+    ) -> IndexMap<Self, MatrixOfFactorSourceIds> {
+        Self::materializability_of_all(factor_source_ids)
+            .into_iter()
+            .filter_map(|(preset, materializability)| match materializability {
+                MatrixTemplateMaterializability::Materializable(matrix) => Some((preset, matrix)),
+                MatrixTemplateMaterializability::MissingFactorSources(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Whether a `MatrixTemplate` can be materialized using some FactorSourceIDs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixTemplateMaterializability {
+    /// Every slot of the template could be assigned a FactorSourceID.
+    Materializable(MatrixOfFactorSourceIds),
+
+    /// The number of additional factor sources of each kind needed to be able
+    /// to materialize the template.
+    MissingFactorSources(IndexMap<FactorSourceKind, usize>),
+}
+
+impl MatrixTemplateMaterializability {
+    pub fn is_materializable(&self) -> bool {
+        matches!(self, Self::Materializable(_))
+    }
+}
+
+impl MatrixTemplate {
+    /// The number of distinct factor sources of each kind needed to materialize
+    /// this template, i.e. slots with the same `FactorSourceTemplate` in different
+    /// roles only count once.
+    pub fn number_of_factor_sources_needed_per_kind(&self) -> IndexMap<FactorSourceKind, usize> {
+        let mut needed = IndexMap::<FactorSourceKind, usize>::new();
+        self.factor_source_templates()
+            .into_iter()
+            .for_each(|template| *needed.entry(template.kind).or_default() += 1);
+        needed
+    }
+
+    /// The number of additional factor sources of each kind needed to materialize
+    /// this template using `factor_source_ids`, empty if it can be materialized.
+    pub fn missing_factor_sources(
+        &self,
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+    ) -> IndexMap<FactorSourceKind, usize> {
+        let supplied = factor_source_ids
+            .into_iter()
+            .unique()
+            .counts_by(|f| f.get_factor_source_kind());
+        self.number_of_factor_sources_needed_per_kind()
+            .into_iter()
+            .filter_map(|(kind, needed)| {
+                let supplied = supplied.get(&kind).cloned().unwrap_or_default();
+                (needed > supplied).then_some((kind, needed - supplied))
+            })
+            .collect()
+    }
+
+    /// Materializes this template using `factor_source_ids` if possible, else
+    /// returns which factor sources are missing.
+    pub fn materializability(
+        self,
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+    ) -> MatrixTemplateMaterializability {
+        let factor_source_ids = factor_source_ids.into_iter().collect_vec();
+        let missing = self.missing_factor_sources(factor_source_ids.clone());
+        if !missing.is_empty() {
+            return MatrixTemplateMaterializability::MissingFactorSources(missing);
+        }
+        let matrix = self
+            .materialize(factor_source_ids)
+            .expect("Should be able to materialize since no factor sources are missing");
+        MatrixTemplateMaterializability::Materializable(matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::upper_case_acronyms)]
+    type SUT = MatrixTemplatePreset;
+
+    #[test]
+    fn all_samples_materialize_all() {
+        let materializable = SUT::materializable(*ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD);
+        assert_eq!(materializable.keys().cloned().collect_vec(), SUT::all());
+        assert_eq!(
+            materializable[&SUT::Config11],
+            MatrixOfFactorSourceIds::sample_config_11()
+        );
+    }
+
+    #[test]
+    fn named() {
+        let named = SUT::Config11.named();
+        assert_eq!(named.name, "Device and Ledger");
+        assert_eq!(named.template, MatrixTemplate::config_11());
+        let names = SUT::all()
+            .into_iter()
+            .map(|p| p.named().name)
+            .collect::<HashSet<_>>();
+        assert_eq!(names.len(), SUT::all().len());
+    }
+
+    #[test]
+    fn needed_per_kind_counts_same_slot_once() {
+        assert_eq!(
+            SUT::Config11
+                .template()
+                .number_of_factor_sources_needed_per_kind(),
+            IndexMap::<FactorSourceKind, usize>::from_iter([
+                (FactorSourceKind::Device, 1),
+                (FactorSourceKind::LedgerHQHardwareWallet, 1),
+                (FactorSourceKind::Password, 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn device_and_ledger() {
+        let all = SUT::materializability_of_all([
+            FactorSourceID::sample_device(),
+            FactorSourceID::sample_ledger(),
+        ]);
+        assert_eq!(all.len(), SUT::all().len());
+        assert_eq!(
+            all[&SUT::Config11],
+            MatrixTemplateMaterializability::MissingFactorSources(IndexMap::from_iter([(
+                FactorSourceKind::Password,
+                1
+            )]))
+        );
+        assert!(!all.values().any(|m| m.is_materializable()));
+    }
+
+    #[test]
+    fn device_and_two_ledgers() {
+        let materializable = SUT::materializable([
+            FactorSourceID::sample_device(),
+            FactorSourceID::sample_ledger(),
+            FactorSourceID::sample_ledger_other(),
+        ]);
+        assert_eq!(
+            materializable.keys().cloned().collect_vec(),
+            vec![SUT::Config21, SUT::Config22, SUT::Config23, SUT::Config24]
+        );
+    }
+
+    #[test]
+    fn duplicates_are_counted_once() {
+        let missing = SUT::Config21.template().missing_factor_sources([
+            FactorSourceID::sample_device(),
+            FactorSourceID::sample_ledger(),
+            FactorSourceID::sample_ledger(),
+        ]);
+        assert_eq!(
+            missing,
+            IndexMap::<FactorSourceKind, usize>::from_iter([(
+                FactorSourceKind::LedgerHQHardwareWallet,
+                1
+            )])
+        );
+    }
+
+    #[test]
+    fn empty_is_missing_everything_needed() {
+        let template = SUT::Config90.template();
+        assert_eq!(
+            template.missing_factor_sources([]),
+            template.number_of_factor_sources_needed_per_kind()
+        );
+    }
+}
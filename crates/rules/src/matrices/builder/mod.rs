@@ -1,9 +1,19 @@
 mod error;
 mod matrix_builder;
+mod matrix_builder_auto_build;
+mod matrix_builder_history;
 mod matrix_builder_unit_tests;
+mod matrix_builder_validation_report;
 mod matrix_template;
+mod matrix_template_presets;
+mod named_matrix_template;
 
 pub use error::*;
 #[allow(unused_imports)]
 pub use matrix_builder::*;
+pub use matrix_builder_auto_build::*;
+pub use matrix_builder_history::*;
+pub use matrix_builder_validation_report::*;
 pub use matrix_template::*;
+pub use matrix_template_presets::*;
+pub use named_matrix_template::*;
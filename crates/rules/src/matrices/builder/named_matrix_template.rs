@@ -0,0 +1,196 @@
+use crate::prelude::*;
+
+/// A `MatrixTemplate` with a human readable name and description, with a stable
+/// JSON format, allowing recommended templates to be shipped as data files.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedMatrixTemplate {
+    /// Short human readable name of the template, e.g. "Device and Ledger".
+    pub name: String,
+
+    /// Human readable description of the template, e.g. explaining which
+    /// factor sources are needed and what they are used for.
+    pub description: String,
+
+    pub template: MatrixTemplate,
+}
+
+impl NamedMatrixTemplate {
+    pub fn new(
+        name: impl AsRef<str>,
+        description: impl AsRef<str>,
+        template: MatrixTemplate,
+    ) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            description: description.as_ref().to_owned(),
+            template,
+        }
+    }
+}
+
+impl HasSampleValues for NamedMatrixTemplate {
+    fn sample() -> Self {
+        MatrixTemplatePreset::Config11.named()
+    }
+
+    fn sample_other() -> Self {
+        MatrixTemplatePreset::Config60.named()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::upper_case_acronyms)]
+    type SUT = NamedMatrixTemplate;
+
+    #[test]
+    fn equality() {
+        assert_eq!(SUT::sample(), SUT::sample());
+        assert_eq!(SUT::sample_other(), SUT::sample_other());
+    }
+
+    #[test]
+    fn inequality() {
+        assert_ne!(SUT::sample(), SUT::sample_other());
+    }
+
+    #[test]
+    fn assert_json_sample() {
+        let sut = SUT::sample();
+        assert_eq_after_json_roundtrip(
+            &sut,
+            r#"
+            {
+              "name": "Device and Ledger",
+              "description": "Sign with this phone and a Ledger, recover with either, confirm with a password.",
+              "template": {
+                "primaryRole": {
+                  "threshold": 2,
+                  "thresholdFactors": [
+                    {
+                      "kind": "device",
+                      "id": 0
+                    },
+                    {
+                      "kind": "ledgerHQHardwareWallet",
+                      "id": 0
+                    }
+                  ],
+                  "overrideFactors": []
+                },
+                "recoveryRole": {
+                  "threshold": 0,
+                  "thresholdFactors": [],
+                  "overrideFactors": [
+                    {
+                      "kind": "device",
+                      "id": 0
+                    },
+                    {
+                      "kind": "ledgerHQHardwareWallet",
+                      "id": 0
+                    }
+                  ]
+                },
+                "confirmationRole": {
+                  "threshold": 0,
+                  "thresholdFactors": [],
+                  "overrideFactors": [
+                    {
+                      "kind": "password",
+                      "id": 0
+                    }
+                  ]
+                },
+                "numberOfDaysUntilAutoConfirm": 14
+              }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn assert_json_sample_other() {
+        let sut = SUT::sample_other();
+        assert_eq_after_json_roundtrip(
+            &sut,
+            r#"
+            {
+              "name": "Trusted contact",
+              "description": "Sign with this phone, recover with a trusted contact, confirm with security questions.",
+              "template": {
+                "primaryRole": {
+                  "threshold": 1,
+                  "thresholdFactors": [
+                    {
+                      "kind": "device",
+                      "id": 0
+                    }
+                  ],
+                  "overrideFactors": []
+                },
+                "recoveryRole": {
+                  "threshold": 0,
+                  "thresholdFactors": [],
+                  "overrideFactors": [
+                    {
+                      "kind": "trustedContact",
+                      "id": 0
+                    }
+                  ]
+                },
+                "confirmationRole": {
+                  "threshold": 0,
+                  "thresholdFactors": [],
+                  "overrideFactors": [
+                    {
+                      "kind": "securityQuestions",
+                      "id": 0
+                    }
+                  ]
+                },
+                "numberOfDaysUntilAutoConfirm": 14
+              }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn load_list_and_materialize() {
+        let json = serde_json::to_string(&vec![SUT::sample(), SUT::sample_other()]).unwrap();
+        let loaded = serde_json::from_str::<Vec<SUT>>(&json).unwrap();
+        assert_eq!(
+            loaded
+                .into_iter()
+                .map(|t| t
+                    .template
+                    .materialize(*ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD)
+                    .unwrap())
+                .collect_vec(),
+            vec![
+                MatrixOfFactorSourceIds::sample_config_11(),
+                MatrixOfFactorSourceIds::sample_config_60()
+            ]
+        );
+    }
+
+    #[test]
+    fn load_invalid_template_is_err() {
+        let mut json = serde_json::to_value(vec![SUT::sample(), SUT::sample_other()]).unwrap();
+        json[1]["template"]["primaryRole"]["overrideFactors"] =
+            serde_json::to_value(vec![FactorSourceTemplate::password()]).unwrap();
+        assert!(serde_json::from_value::<Vec<SUT>>(json).is_err());
+    }
+
+    #[test]
+    fn json_roundtrip_all_presets() {
+        MatrixTemplatePreset::all()
+            .into_iter()
+            .map(|p| p.template())
+            .for_each(|t| assert_json_roundtrip(&t));
+    }
+}
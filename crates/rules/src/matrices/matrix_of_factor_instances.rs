@@ -2,6 +2,16 @@ use crate::prelude::*;
 
 pub type MatrixOfFactorInstances = AbstractMatrixBuilderOrBuilt<FactorInstance, (), ()>;
 
+impl IsValidatedOnDeserialization for MatrixOfFactorInstances {
+    type Error = FactorNotSecurified;
+
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        self.primary_role.validate_securified()?;
+        self.recovery_role.validate_securified()?;
+        self.confirmation_role.validate_securified()
+    }
+}
+
 impl HasFactorInstances for MatrixOfFactorInstances {
     fn unique_factor_instances(&self) -> IndexSet<FactorInstance> {
         let mut set = IndexSet::new();
@@ -13,17 +23,37 @@ impl HasFactorInstances for MatrixOfFactorInstances {
 }
 
 impl MatrixOfFactorInstances {
-    fn from_matrix_of_sources(matrix_of_sources: MatrixOfFactorSources) -> Self {
-        let mut consuming_instances = MnemonicWithPassphrase::derive_instances_for_factor_sources(
-            sargon::NetworkID::Mainnet,
-            1,
-            [DerivationPreset::AccountMfa],
-            matrix_of_sources.all_factors().into_iter().cloned(),
-        );
+    /// Maps `matrix_of_sources` to a `MatrixOfFactorInstances` using securified
+    /// (MFA) FactorInstances for entities of kind `entity_kind` on `network_id`,
+    /// derived using the sample mnemonics of the factor sources.
+    ///
+    /// # Throws
+    /// If `matrix_of_sources` contains any address based factor source, e.g. a
+    /// trusted contact, since no instances can be derived for it.
+    pub(crate) fn from_matrix_of_sources_for_entity_kind_on_network(
+        matrix_of_sources: MatrixOfFactorSources,
+        entity_kind: CAP26EntityKind,
+        network_id: NetworkID,
+    ) -> Result<Self, MatrixFulfillmentError> {
+        let mut consuming_instances =
+            MnemonicWithPassphrase::derive_mfa_instances_for_factor_sources(
+                network_id,
+                entity_kind,
+                1,
+                matrix_of_sources.all_factors().into_iter().cloned(),
+            );
 
         Self::fulfilling_matrix_of_factor_sources_with_instances(
             &mut consuming_instances,
-            matrix_of_sources.clone(),
+            matrix_of_sources,
+        )
+    }
+
+    fn from_matrix_of_sources(matrix_of_sources: MatrixOfFactorSources) -> Self {
+        Self::from_matrix_of_sources_for_entity_kind_on_network(
+            matrix_of_sources,
+            CAP26EntityKind::Account,
+            NetworkID::Mainnet,
         )
         .unwrap()
     }
@@ -55,7 +85,58 @@ impl MatrixOfFactorInstances {
     pub fn fulfilling_matrix_of_factor_sources_with_instances(
         consuming_instances: &mut IndexMap<FactorSourceIDFromHash, FactorInstances>,
         matrix_of_factor_sources: MatrixOfFactorSources,
-    ) -> Result<Self, CommonError> {
+    ) -> Result<Self, MatrixFulfillmentError> {
+        let mut instances_by_factor_source_id = consuming_instances
+            .iter()
+            .map(|(id, instances)| {
+                (
+                    FactorSourceID::from(*id),
+                    instances
+                        .iter()
+                        .map(|i| FactorInstance::from(i.clone()))
+                        .collect::<IndexSet<_>>(),
+                )
+            })
+            .collect::<IndexMap<_, _>>();
+
+        let matrix = Self::fulfilling_matrix_of_factor_sources_with_instances_by_factor_source_id(
+            &mut instances_by_factor_source_id,
+            matrix_of_factor_sources,
+        )?;
+
+        // Only HD instances of hash based factor sources can be in `consuming_instances`.
+        for instance in matrix.all_factors() {
+            let Ok(fsid) = FactorSourceIDFromHash::try_from(instance.factor_source_id) else {
+                continue;
+            };
+            let Ok(to_remove) = HierarchicalDeterministicFactorInstance::try_from(instance.clone())
+            else {
+                continue;
+            };
+            let Some(existing) = consuming_instances.get_mut(&fsid) else {
+                continue;
+            };
+
+            // We remove at the beginning of the list first.
+            existing.shift_remove(&to_remove);
+
+            if existing.is_empty() {
+                // not needed per se, but feels prudent to "prune".
+                consuming_instances.shift_remove_entry(&fsid);
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    /// Like `fulfilling_matrix_of_factor_sources_with_instances` but `consuming_instances`
+    /// is keyed by any kind of FactorSourceID, not only hash based ones, e.g.
+    /// address based trusted contacts, and the instances might be non HD, e.g.
+    /// physical badges.
+    pub fn fulfilling_matrix_of_factor_sources_with_instances_by_factor_source_id(
+        consuming_instances: &mut IndexMap<FactorSourceID, IndexSet<FactorInstance>>,
+        matrix_of_factor_sources: MatrixOfFactorSources,
+    ) -> Result<Self, MatrixFulfillmentError> {
         let instances = &consuming_instances.clone();
 
         let primary_role =
@@ -86,18 +167,234 @@ impl MatrixOfFactorInstances {
         // Now that we have assigned instances, **possibly the SAME INSTANCE to multiple roles**,
         // lets delete them from the `consuming_instances` map.
         for instance in matrix.all_factors() {
-            let fsid = &FactorSourceIDFromHash::try_from(instance.factor_source_id).unwrap();
-            let existing = consuming_instances.get_mut(fsid).unwrap();
-
-            let to_remove =
-                HierarchicalDeterministicFactorInstance::try_from(instance.clone()).unwrap();
+            let fsid = instance.factor_source_id;
+            let Some(existing) = consuming_instances.get_mut(&fsid) else {
+                continue;
+            };
 
             // We remove at the beginning of the list first.
-            existing.shift_remove(&to_remove);
+            existing.shift_remove(instance);
 
             if existing.is_empty() {
                 // not needed per se, but feels prudent to "prune".
-                consuming_instances.shift_remove_entry(fsid);
+                consuming_instances.shift_remove_entry(&fsid);
+            }
+        }
+
+        Ok(matrix)
+    }
+}
+
+/// Fulfilling a `MatrixOfFactorSources` with FactorInstances failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MatrixFulfillmentError {
+    #[error(transparent)]
+    FactorNotSecurified(#[from] FactorNotSecurified),
+
+    #[error(transparent)]
+    Sargon(#[from] CommonError),
+}
+
+/// Fulfilling a `MatrixOfFactorSources` for a batch of entities failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BatchFulfillmentError {
+    /// Not enough instances in the pools, `missing` is the number of additional
+    /// instances needed per factor source, per entity kind.
+    #[error("Not enough factor instances, missing: {missing:?}")]
+    InstancesShortfall {
+        missing: IndexMap<CAP26EntityKind, IndexMap<FactorSourceID, usize>>,
+    },
+
+    #[error("Entity {entity:?} occurs more than once")]
+    DuplicateEntity { entity: AddressOfAccountOrPersona },
+
+    #[error("FactorInstance used in multiple matrices: {factor_instance:?}")]
+    FactorInstanceUsedInMultipleMatrices { factor_instance: FactorInstance },
+
+    /// A FactorInstance assigned to `entity` is derived on another network
+    /// than the network of `entity`.
+    #[error("Entity {entity:?} is on network {expected:?}, but FactorInstance is derived on network {found:?}: {factor_instance:?}")]
+    EntityNetworkMismatch {
+        entity: AddressOfAccountOrPersona,
+        factor_instance: FactorInstance,
+        expected: NetworkID,
+        found: NetworkID,
+    },
+
+    #[error(transparent)]
+    Fulfillment(#[from] MatrixFulfillmentError),
+}
+
+impl MatrixOfFactorInstances {
+    /// Fulfills `matrix_of_factor_sources` once for each of `entities`, using
+    /// instances from the shared pool of the entity kind of each entity in
+    /// `consuming_instances`, returning one `MatrixOfFactorInstances` per entity,
+    /// in the order of `entities`.
+    ///
+    /// No FactorInstance is ever used in two different matrices, and the
+    /// used instances are removed from `consuming_instances`.
+    ///
+    /// # Throws
+    /// If any entity occurs more than once in `entities`, if the pool of any
+    /// entity kind does not contain at least one instance per entity of that
+    /// kind for each factor source of the matrix, in which case the number of
+    /// missing instances per factor source per entity kind is reported, or if
+    /// any instance assigned to an entity is derived on another network than
+    /// the network of the entity. In all cases `consuming_instances` is left
+    /// untouched.
+    pub fn fulfilling_matrix_of_factor_sources_for_entities(
+        consuming_instances: &mut IndexMap<
+            CAP26EntityKind,
+            IndexMap<FactorSourceID, IndexSet<FactorInstance>>,
+        >,
+        matrix_of_factor_sources: MatrixOfFactorSources,
+        entities: impl IntoIterator<Item = AddressOfAccountOrPersona>,
+    ) -> Result<IndexMap<AddressOfAccountOrPersona, Self>, BatchFulfillmentError> {
+        let mut unique_entities = IndexSet::new();
+        for entity in entities {
+            if !unique_entities.insert(entity) {
+                return Err(BatchFulfillmentError::DuplicateEntity { entity });
+            }
+        }
+        let needed_per_kind = unique_entities
+            .iter()
+            .counts_by(|e| e.get_entity_kind())
+            .into_iter()
+            .collect::<IndexMap<_, _>>();
+
+        let factor_source_ids = [
+            matrix_of_factor_sources.primary().all_factors(),
+            matrix_of_factor_sources.recovery().all_factors(),
+            matrix_of_factor_sources.confirmation().all_factors(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|f| f.id())
+        .collect::<IndexSet<_>>();
+
+        let missing = needed_per_kind
+            .iter()
+            .filter_map(|(entity_kind, needed)| {
+                let pool = consuming_instances.get(entity_kind);
+                let missing = factor_source_ids
+                    .iter()
+                    .filter_map(|id| {
+                        let available = pool.and_then(|p| p.get(id)).map_or(0, |xs| xs.len());
+                        (available < *needed).then_some((*id, needed - available))
+                    })
+                    .collect::<IndexMap<_, _>>();
+                (!missing.is_empty()).then_some((*entity_kind, missing))
+            })
+            .collect::<IndexMap<_, _>>();
+        if !missing.is_empty() {
+            return Err(BatchFulfillmentError::InstancesShortfall { missing });
+        }
+
+        let mut pools = consuming_instances.clone();
+        let mut used = IndexSet::<FactorInstance>::new();
+        let mut matrices = IndexMap::new();
+        for entity in unique_entities {
+            let pool = pools.entry(entity.get_entity_kind()).or_default();
+            let matrix =
+                Self::fulfilling_matrix_of_factor_sources_with_instances_by_factor_source_id(
+                    pool,
+                    matrix_of_factor_sources.clone(),
+                )?;
+            let expected = entity.network_id();
+            for factor_instance in matrix.unique_factor_instances() {
+                if let Some(found) =
+                    HierarchicalDeterministicFactorInstance::try_from(factor_instance.clone())
+                        .ok()
+                        .and_then(|hd| network_and_entity_kind(&hd.derivation_path()))
+                        .map(|(network_id, _)| network_id)
+                        .filter(|network_id| *network_id != expected)
+                {
+                    return Err(BatchFulfillmentError::EntityNetworkMismatch {
+                        entity,
+                        factor_instance,
+                        expected,
+                        found,
+                    });
+                }
+                if !used.insert(factor_instance.clone()) {
+                    return Err(
+                        BatchFulfillmentError::FactorInstanceUsedInMultipleMatrices {
+                            factor_instance,
+                        },
+                    );
+                }
+            }
+            matrices.insert(entity, matrix);
+        }
+
+        *consuming_instances = pools;
+        Ok(matrices)
+    }
+}
+
+impl MatrixOfFactorInstances {
+    /// "Rotation" mode of fulfilling `matrix_of_factor_sources`, used when
+    /// changing the shield of an already securified entity, which currently is
+    /// securified with `current`.
+    ///
+    /// For every factor source of `matrix_of_factor_sources` which is also used
+    /// in `current` its existing FactorInstance is reused, keeping the public key
+    /// stable. Fresh instances from `consuming_instances` are only used - and
+    /// removed - for newly added factor sources, instances of reused factor
+    /// sources are left untouched in `consuming_instances`.
+    ///
+    /// # Throws
+    /// If `consuming_instances` lacks an instance for any newly added factor
+    /// source, in which case `consuming_instances` is left untouched.
+    pub fn fulfilling_matrix_of_factor_sources_reusing_instances_of(
+        current: &Self,
+        consuming_instances: &mut IndexMap<FactorSourceID, IndexSet<FactorInstance>>,
+        matrix_of_factor_sources: MatrixOfFactorSources,
+    ) -> Result<Self, MatrixFulfillmentError> {
+        let factor_source_ids = matrix_of_factor_sources
+            .all_factors()
+            .into_iter()
+            .map(|f| f.id())
+            .collect::<IndexSet<_>>();
+
+        let reused = current
+            .unique_factor_instances()
+            .into_iter()
+            .filter(|i| factor_source_ids.contains(&i.factor_source_id))
+            .unique_by(|i| i.factor_source_id)
+            .map(|i| (i.factor_source_id, i))
+            .collect::<IndexMap<_, _>>();
+
+        let mut pool = consuming_instances
+            .iter()
+            .filter(|(id, _)| !reused.contains_key(*id))
+            .map(|(id, instances)| (*id, instances.clone()))
+            .chain(
+                reused
+                    .iter()
+                    .map(|(id, instance)| (*id, IndexSet::from_iter([instance.clone()]))),
+            )
+            .collect::<IndexMap<_, _>>();
+
+        let matrix = Self::fulfilling_matrix_of_factor_sources_with_instances_by_factor_source_id(
+            &mut pool,
+            matrix_of_factor_sources,
+        )?;
+
+        // Commit the consumption of fresh instances, i.e. of factor sources not reused.
+        let fresh_ids = consuming_instances
+            .keys()
+            .filter(|id| !reused.contains_key(*id))
+            .cloned()
+            .collect_vec();
+        for id in fresh_ids {
+            match pool.shift_remove(&id) {
+                Some(left) => {
+                    consuming_instances.insert(id, left);
+                }
+                None => {
+                    consuming_instances.shift_remove(&id);
+                }
             }
         }
 
@@ -128,6 +425,501 @@ mod tests {
         );
     }
 
+    fn trusted_contact_instance() -> FactorInstance {
+        FactorInstance {
+            factor_source_id: FactorSourceID::sample_trusted_contact(),
+            badge: FactorInstance::sample_other().badge,
+        }
+    }
+
+    #[test]
+    fn fulfilling_address_based_factor_source_with_non_hd_instance() {
+        let matrix_of_sources = MatrixOfFactorSources::new(
+            MatrixOfFactorSourceIds::sample_config_51(),
+            &FactorSources::sample_values_all(),
+        )
+        .unwrap();
+        let hash_based_sources = matrix_of_sources
+            .all_factors()
+            .into_iter()
+            .filter(|f| f.id().get_factor_source_kind() != FactorSourceKind::TrustedContact)
+            .cloned()
+            .collect_vec();
+        let mut instances = MnemonicWithPassphrase::derive_instances_for_factor_sources(
+            NetworkID::Mainnet,
+            1,
+            [DerivationPreset::AccountMfa],
+            hash_based_sources,
+        )
+        .into_iter()
+        .map(|(id, instances)| {
+            (
+                FactorSourceID::from(id),
+                instances
+                    .iter()
+                    .map(|i| FactorInstance::from(i.clone()))
+                    .collect::<IndexSet<_>>(),
+            )
+        })
+        .collect::<IndexMap<_, _>>();
+        instances.insert(
+            FactorSourceID::sample_trusted_contact(),
+            IndexSet::from_iter([trusted_contact_instance()]),
+        );
+
+        let sut = SUT::fulfilling_matrix_of_factor_sources_with_instances_by_factor_source_id(
+            &mut instances,
+            matrix_of_sources,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sut.recovery().get_override_factors(),
+            &vec![trusted_contact_instance()]
+        );
+        assert!(instances.is_empty());
+    }
+
+    #[test]
+    fn err_if_no_instance_found_for_address_based_factor_source() {
+        let matrix_of_sources = MatrixOfFactorSources::new(
+            MatrixOfFactorSourceIds::sample_config_60(),
+            &FactorSources::sample_values_all(),
+        )
+        .unwrap();
+        assert!(matches!(
+            SUT::fulfilling_matrix_of_factor_sources_with_instances_by_factor_source_id(
+                &mut IndexMap::kv(
+                    FactorSourceID::sample_device(),
+                    IndexSet::from_iter([FactorInstance::sample_other()])
+                ),
+                matrix_of_sources
+            ),
+            Err(MatrixFulfillmentError::Sargon(
+                CommonError::MissingFactorMappingInstancesIntoRole
+            ))
+        ));
+    }
+
+    fn pool_for(
+        matrix_of_sources: &MatrixOfFactorSources,
+        quantity_per_factor: usize,
+    ) -> IndexMap<FactorSourceID, IndexSet<FactorInstance>> {
+        pool_for_entity_kind(
+            matrix_of_sources,
+            CAP26EntityKind::Account,
+            quantity_per_factor,
+        )
+    }
+
+    fn pool_for_entity_kind(
+        matrix_of_sources: &MatrixOfFactorSources,
+        entity_kind: CAP26EntityKind,
+        quantity_per_factor: usize,
+    ) -> IndexMap<FactorSourceID, IndexSet<FactorInstance>> {
+        MnemonicWithPassphrase::derive_instances_for_factor_sources(
+            NetworkID::Mainnet,
+            quantity_per_factor,
+            [DerivationPreset::mfa_for_entity_kind(entity_kind)],
+            matrix_of_sources.all_factors().into_iter().cloned(),
+        )
+        .into_iter()
+        .map(|(id, instances)| {
+            (
+                FactorSourceID::from(id),
+                instances
+                    .iter()
+                    .map(|i| FactorInstance::from(i.clone()))
+                    .collect::<IndexSet<_>>(),
+            )
+        })
+        .collect()
+    }
+
+    fn pools_for_sample(
+        quantity_per_factor: usize,
+    ) -> IndexMap<CAP26EntityKind, IndexMap<FactorSourceID, IndexSet<FactorInstance>>> {
+        [CAP26EntityKind::Account, CAP26EntityKind::Identity]
+            .into_iter()
+            .map(|entity_kind| {
+                (
+                    entity_kind,
+                    pool_for_entity_kind(
+                        &MatrixOfFactorSources::sample(),
+                        entity_kind,
+                        quantity_per_factor,
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn pool_for_sample(
+        quantity_per_factor: usize,
+    ) -> IndexMap<FactorSourceID, IndexSet<FactorInstance>> {
+        pool_for(&MatrixOfFactorSources::sample(), quantity_per_factor)
+    }
+
+    fn account(address: sargon::AccountAddress) -> AddressOfAccountOrPersona {
+        AddressOfAccountOrPersona::Account(address)
+    }
+
+    fn identity(address: sargon::IdentityAddress) -> AddressOfAccountOrPersona {
+        AddressOfAccountOrPersona::Identity(address)
+    }
+
+    #[test]
+    fn batch_fulfilling_never_reuses_instances() {
+        let mut pools = pools_for_sample(4);
+        let before = pools.clone();
+        let entities = vec![
+            account(sargon::AccountAddress::sample_mainnet()),
+            identity(sargon::IdentityAddress::sample_mainnet()),
+            account(sargon::AccountAddress::sample_mainnet_other()),
+            identity(sargon::IdentityAddress::sample_mainnet_other()),
+        ];
+        let matrices = SUT::fulfilling_matrix_of_factor_sources_for_entities(
+            &mut pools,
+            MatrixOfFactorSources::sample(),
+            entities.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(matrices.keys().cloned().collect_vec(), entities);
+        let all_instances = matrices
+            .values()
+            .flat_map(|m| m.unique_factor_instances())
+            .collect_vec();
+        assert_eq!(
+            all_instances.len(),
+            all_instances.iter().unique().count(),
+            "No instance should be used in two matrices"
+        );
+
+        // each matrix uses instances of the pool of the kind of its entity
+        matrices.iter().for_each(|(entity, matrix)| {
+            let pool = before.get(&entity.get_entity_kind()).unwrap();
+            assert!(matrix
+                .unique_factor_instances()
+                .iter()
+                .all(|i| pool.values().flatten().contains(i)));
+        });
+
+        // two instances of each of the three factor sources left for both
+        // accounts and identities
+        assert_eq!(
+            pools
+                .values()
+                .map(|pool| pool.values().map(|xs| xs.len()).collect_vec())
+                .collect_vec(),
+            vec![vec![2, 2, 2], vec![2, 2, 2]]
+        );
+        assert!(pools
+            .values()
+            .flat_map(|pool| pool.values().flatten())
+            .all(|i| !all_instances.contains(i)));
+    }
+
+    #[test]
+    fn batch_fulfilling_shortfall_is_reported_per_entity_kind_and_pools_untouched() {
+        let mut pools = pools_for_sample(1);
+        pools
+            .get_mut(&CAP26EntityKind::Account)
+            .unwrap()
+            .shift_remove(&FactorSourceID::sample_password());
+        let before = pools.clone();
+
+        let res = SUT::fulfilling_matrix_of_factor_sources_for_entities(
+            &mut pools,
+            MatrixOfFactorSources::sample(),
+            [
+                account(sargon::AccountAddress::sample_mainnet()),
+                account(sargon::AccountAddress::sample_mainnet_other()),
+                identity(sargon::IdentityAddress::sample_mainnet()),
+            ],
+        );
+
+        let Err(BatchFulfillmentError::InstancesShortfall { missing }) = res else {
+            panic!("Expected shortfall, got: {:?}", res);
+        };
+        assert_eq!(
+            missing,
+            IndexMap::<CAP26EntityKind, IndexMap<FactorSourceID, usize>>::from_iter([(
+                CAP26EntityKind::Account,
+                IndexMap::from_iter([
+                    (FactorSourceID::sample_device(), 1),
+                    (FactorSourceID::sample_ledger(), 1),
+                    (FactorSourceID::sample_password(), 2),
+                ])
+            )])
+        );
+        assert_eq!(pools, before);
+    }
+
+    #[test]
+    fn batch_fulfilling_err_if_entity_on_other_network_than_instances() {
+        let mut pools = pools_for_sample(2);
+        let before = pools.clone();
+        let stokenet_account = account(sargon::AccountAddress::sample_stokenet());
+
+        let res = SUT::fulfilling_matrix_of_factor_sources_for_entities(
+            &mut pools,
+            MatrixOfFactorSources::sample(),
+            [
+                account(sargon::AccountAddress::sample_mainnet()),
+                stokenet_account.clone(),
+            ],
+        );
+
+        let Err(BatchFulfillmentError::EntityNetworkMismatch {
+            entity,
+            expected,
+            found,
+            ..
+        }) = res
+        else {
+            panic!("Expected network mismatch, got: {:?}", res);
+        };
+        assert_eq!(entity, stokenet_account);
+        assert_eq!(expected, NetworkID::Stokenet);
+        assert_eq!(found, NetworkID::Mainnet);
+        assert_eq!(pools, before);
+    }
+
+    #[test]
+    fn batch_fulfilling_err_if_duplicate_entity_and_pools_untouched() {
+        let mut pools = pools_for_sample(4);
+        let before = pools.clone();
+        let duplicate = account(sargon::AccountAddress::sample_mainnet());
+
+        let res = SUT::fulfilling_matrix_of_factor_sources_for_entities(
+            &mut pools,
+            MatrixOfFactorSources::sample(),
+            [
+                duplicate.clone(),
+                identity(sargon::IdentityAddress::sample_mainnet()),
+                duplicate.clone(),
+            ],
+        );
+
+        assert_eq!(
+            res,
+            Err(BatchFulfillmentError::DuplicateEntity { entity: duplicate })
+        );
+        assert_eq!(pools, before);
+    }
+
+    #[test]
+    fn batch_fulfilling_err_if_instance_in_pools_of_multiple_entity_kinds() {
+        let pool = pool_for_sample(1);
+        let mut pools = IndexMap::from_iter([
+            (CAP26EntityKind::Account, pool.clone()),
+            (CAP26EntityKind::Identity, pool),
+        ]);
+        let before = pools.clone();
+
+        let res = SUT::fulfilling_matrix_of_factor_sources_for_entities(
+            &mut pools,
+            MatrixOfFactorSources::sample(),
+            [
+                account(sargon::AccountAddress::sample_mainnet()),
+                identity(sargon::IdentityAddress::sample_mainnet()),
+            ],
+        );
+
+        assert!(matches!(
+            res,
+            Err(BatchFulfillmentError::FactorInstanceUsedInMultipleMatrices { .. })
+        ));
+        assert_eq!(pools, before);
+    }
+
+    #[test]
+    fn batch_fulfilling_no_entities() {
+        let mut pools = pools_for_sample(1);
+        let before = pools.clone();
+        let matrices = SUT::fulfilling_matrix_of_factor_sources_for_entities(
+            &mut pools,
+            MatrixOfFactorSources::sample(),
+            [],
+        )
+        .unwrap();
+        assert!(matrices.is_empty());
+        assert_eq!(pools, before);
+    }
+
+    /// Pool of instances for `matrix_of_sources` not used by `SUT::sample()`.
+    fn fresh_pool_for(
+        matrix_of_sources: &MatrixOfFactorSources,
+    ) -> IndexMap<FactorSourceID, IndexSet<FactorInstance>> {
+        let used = SUT::sample().unique_factor_instances();
+        pool_for(matrix_of_sources, 2)
+            .into_iter()
+            .map(|(id, instances)| {
+                (
+                    id,
+                    instances
+                        .into_iter()
+                        .filter(|i| !used.contains(i))
+                        .collect::<IndexSet<_>>(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rotation_unchanged_matrix_reuses_all_instances() {
+        let current = SUT::sample();
+        let mut pool = fresh_pool_for(&MatrixOfFactorSources::sample());
+        let before = pool.clone();
+        let sut = SUT::fulfilling_matrix_of_factor_sources_reusing_instances_of(
+            &current,
+            &mut pool,
+            MatrixOfFactorSources::sample(),
+        )
+        .unwrap();
+        assert_eq!(sut, current);
+        assert_eq!(pool, before);
+    }
+
+    #[test]
+    fn rotation_only_consumes_instances_of_added_factors() {
+        let current = SUT::sample();
+        let current_instances = current.unique_factor_instances();
+        let current_ids = current_instances
+            .iter()
+            .map(|i| i.factor_source_id)
+            .collect::<IndexSet<_>>();
+        let matrix_of_sources = MatrixOfFactorSources::sample_other();
+        let mut pool = fresh_pool_for(&matrix_of_sources);
+        let before = pool.clone();
+
+        let sut = SUT::fulfilling_matrix_of_factor_sources_reusing_instances_of(
+            &current,
+            &mut pool,
+            matrix_of_sources,
+        )
+        .unwrap();
+
+        let (reused, fresh): (Vec<_>, Vec<_>) = sut
+            .unique_factor_instances()
+            .into_iter()
+            .partition(|i| current_ids.contains(&i.factor_source_id));
+        assert!(!reused.is_empty());
+        assert!(!fresh.is_empty());
+        assert!(reused.iter().all(|i| current_instances.contains(i)));
+        for instance in fresh {
+            assert!(!current_instances.contains(&instance));
+            assert!(before[&instance.factor_source_id].contains(&instance));
+            assert!(!pool
+                .get(&instance.factor_source_id)
+                .is_some_and(|xs| xs.contains(&instance)));
+        }
+        for id in reused.iter().map(|i| i.factor_source_id) {
+            assert_eq!(pool.get(&id), before.get(&id));
+        }
+    }
+
+    #[test]
+    fn rotation_err_if_no_instance_for_added_factor_pool_untouched() {
+        let current = SUT::sample();
+        let mut pool = IndexMap::<FactorSourceID, IndexSet<FactorInstance>>::new();
+        assert!(matches!(
+            SUT::fulfilling_matrix_of_factor_sources_reusing_instances_of(
+                &current,
+                &mut pool,
+                MatrixOfFactorSources::sample_other(),
+            ),
+            Err(MatrixFulfillmentError::Sargon(
+                CommonError::MissingFactorMappingInstancesIntoRole
+            ))
+        ));
+        assert!(pool.is_empty());
+    }
+
+    fn mfa_instances_for_sample(
+        entity_kind: CAP26EntityKind,
+        network_id: NetworkID,
+    ) -> IndexSet<FactorInstance> {
+        MnemonicWithPassphrase::derive_mfa_instances_for_factor_sources(
+            network_id,
+            entity_kind,
+            1,
+            MatrixOfFactorSources::sample()
+                .all_factors()
+                .into_iter()
+                .cloned(),
+        )
+        .into_values()
+        .flat_map(|instances| {
+            instances
+                .iter()
+                .map(|i| FactorInstance::from(i.clone()))
+                .collect_vec()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn identity_on_stokenet_uses_identity_mfa_instances() {
+        let sut = SUT::from_matrix_of_sources_for_entity_kind_on_network(
+            MatrixOfFactorSources::sample(),
+            CAP26EntityKind::Identity,
+            NetworkID::Stokenet,
+        )
+        .unwrap();
+        assert_eq!(
+            sut.unique_factor_instances(),
+            mfa_instances_for_sample(CAP26EntityKind::Identity, NetworkID::Stokenet)
+        );
+        assert_ne!(sut, SUT::sample());
+    }
+
+    #[test]
+    fn account_on_mainnet_is_sample() {
+        assert_eq!(
+            SUT::from_matrix_of_sources_for_entity_kind_on_network(
+                MatrixOfFactorSources::sample(),
+                CAP26EntityKind::Account,
+                NetworkID::Mainnet,
+            )
+            .unwrap(),
+            SUT::sample()
+        );
+    }
+
+    #[test]
+    fn entity_kind_and_network_yield_distinct_instances() {
+        let account_mainnet =
+            mfa_instances_for_sample(CAP26EntityKind::Account, NetworkID::Mainnet);
+        let account_stokenet =
+            mfa_instances_for_sample(CAP26EntityKind::Account, NetworkID::Stokenet);
+        let identity_mainnet =
+            mfa_instances_for_sample(CAP26EntityKind::Identity, NetworkID::Mainnet);
+        assert!(account_mainnet.is_disjoint(&account_stokenet));
+        assert!(account_mainnet.is_disjoint(&identity_mainnet));
+        assert!(account_stokenet.is_disjoint(&identity_mainnet));
+    }
+
+    #[test]
+    fn err_if_address_based_factor_source_for_entity_kind_on_network() {
+        let matrix_of_sources = MatrixOfFactorSources::new(
+            MatrixOfFactorSourceIds::sample_config_51(),
+            &FactorSources::sample_values_all(),
+        )
+        .unwrap();
+        assert!(matches!(
+            SUT::from_matrix_of_sources_for_entity_kind_on_network(
+                matrix_of_sources,
+                CAP26EntityKind::Account,
+                NetworkID::Mainnet,
+            ),
+            Err(MatrixFulfillmentError::Sargon(
+                CommonError::MissingFactorMappingInstancesIntoRole
+            ))
+        ));
+    }
+
     #[test]
     fn err_if_no_instance_found_for_factor_source() {
         assert!(matches!(
@@ -135,7 +927,9 @@ mod tests {
                 &mut IndexMap::new(),
                 MatrixOfFactorSources::sample()
             ),
-            Err(CommonError::MissingFactorMappingInstancesIntoRole)
+            Err(MatrixFulfillmentError::Sargon(
+                CommonError::MissingFactorMappingInstancesIntoRole
+            ))
         ));
     }
 
@@ -149,10 +943,43 @@ mod tests {
                 ),
                 MatrixOfFactorSources::sample()
             ),
-            Err(CommonError::MissingFactorMappingInstancesIntoRole)
+            Err(MatrixFulfillmentError::Sargon(
+                CommonError::MissingFactorMappingInstancesIntoRole
+            ))
         ));
     }
 
+    #[test]
+    fn err_if_unsecurified_instance_names_role_list_and_instance() {
+        let unsecurified = HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_10_unsecurified_at_index(0);
+        let mut pool = pool_for_sample(1);
+        pool.insert(
+            FactorSourceID::sample_device(),
+            IndexSet::from_iter([FactorInstance::from(unsecurified.clone())]),
+        );
+
+        let Err(MatrixFulfillmentError::FactorNotSecurified(err)) =
+            SUT::fulfilling_matrix_of_factor_sources_with_instances_by_factor_source_id(
+                &mut pool,
+                MatrixOfFactorSources::sample(),
+            )
+        else {
+            panic!("Expected FactorNotSecurified");
+        };
+        assert_eq!(err.role, RoleKind::Primary);
+        assert_eq!(err.list, FactorListKind::Threshold);
+        assert_eq!(err.factor_instance, unsecurified);
+    }
+
+    #[test]
+    fn deserializing_unsecurified_instance_is_err() {
+        let unsecurified = HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_10_unsecurified_at_index(0);
+        let mut json = serde_json::to_value(SUT::sample()).unwrap();
+        json["recoveryRole"]["overrideFactors"] =
+            serde_json::json!([FactorInstance::from(unsecurified)]);
+        assert!(serde_json::from_value::<SUT>(json).is_err());
+    }
+
     #[test]
     fn assert_json_sample() {
         let sut = SUT::sample();
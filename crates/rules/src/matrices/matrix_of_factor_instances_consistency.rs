@@ -0,0 +1,392 @@
+use crate::prelude::*;
+
+use sargon::DerivationPath;
+
+/// An inconsistency found in a `MatrixOfFactorInstances`, e.g. instances
+/// derived on different networks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FactorInstanceInconsistency {
+    /// The HD instance is not derived using a CAP26 derivation path, so it has
+    /// neither network nor entity kind we can check.
+    NotCAP26 { factor_instance: FactorInstance },
+
+    /// The HD instance is derived on another network than the first HD instance
+    /// of the matrix.
+    NetworkMismatch {
+        factor_instance: FactorInstance,
+        expected: NetworkID,
+        found: NetworkID,
+    },
+
+    /// The HD instance is derived for another entity kind than the first HD
+    /// instance of the matrix.
+    EntityKindMismatch {
+        factor_instance: FactorInstance,
+        expected: CAP26EntityKind,
+        found: CAP26EntityKind,
+    },
+
+    /// The HD instance is not in the `Securified` key space.
+    NotSecurified {
+        factor_instance: FactorInstance,
+        key_space: KeySpace,
+    },
+
+    /// The list of kind `list` of `role` has `found` many instances, but the
+    /// expected matrix of factor source ids has `expected` many factors.
+    NumberOfFactorsMismatch {
+        role: RoleKind,
+        list: FactorListKind,
+        expected: usize,
+        found: usize,
+    },
+
+    /// The instance at `index` in the list of kind `list` of `role` does not
+    /// belong to the factor source at the same slot of the expected matrix of
+    /// factor source ids.
+    FactorSourceMismatch {
+        role: RoleKind,
+        list: FactorListKind,
+        index: usize,
+        expected: FactorSourceID,
+        found: FactorSourceID,
+    },
+}
+
+/// The result of validating the consistency of a `MatrixOfFactorInstances`,
+/// see `MatrixOfFactorInstances::consistency_report`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixOfFactorInstancesConsistencyReport {
+    /// The network of the first HD instance of the matrix which all other HD
+    /// instances are checked against, `None` if there are no CAP26 instances.
+    pub network_id: Option<NetworkID>,
+
+    /// The entity kind of the first HD instance of the matrix which all other HD
+    /// instances are checked against, `None` if there are no CAP26 instances.
+    pub entity_kind: Option<CAP26EntityKind>,
+
+    pub inconsistencies: Vec<FactorInstanceInconsistency>,
+}
+
+impl MatrixOfFactorInstancesConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+}
+
+pub(crate) fn network_and_entity_kind(
+    path: &DerivationPath,
+) -> Option<(NetworkID, CAP26EntityKind)> {
+    match path {
+        DerivationPath::Account { value } => Some((value.network_id, CAP26EntityKind::Account)),
+        DerivationPath::Identity { value } => Some((value.network_id, CAP26EntityKind::Identity)),
+        DerivationPath::Bip44Like { .. } => None,
+    }
+}
+
+impl MatrixOfFactorInstances {
+    /// Validates that all HD instances of this matrix share the same network and
+    /// entity kind and are in the `Securified` key space, and that each instance
+    /// belongs to the factor source of the same slot of `matrix_of_factor_source_ids`,
+    /// returning every inconsistency found.
+    pub fn consistency_report(
+        &self,
+        matrix_of_factor_source_ids: &MatrixOfFactorSourceIds,
+    ) -> MatrixOfFactorInstancesConsistencyReport {
+        let mut inconsistencies = Vec::new();
+        let mut expected: Option<(NetworkID, CAP26EntityKind)> = None;
+
+        for factor_instance in self.unique_factor_instances() {
+            let Ok(hd) = HierarchicalDeterministicFactorInstance::try_from(factor_instance.clone())
+            else {
+                // Non HD instances, e.g. physical badges, have no derivation path.
+                continue;
+            };
+
+            if let Some(key_space) = factor_instance
+                .maybe_key_space()
+                .filter(|key_space| *key_space != KeySpace::Securified)
+            {
+                inconsistencies.push(FactorInstanceInconsistency::NotSecurified {
+                    factor_instance: factor_instance.clone(),
+                    key_space,
+                });
+            }
+
+            let Some((network_id, entity_kind)) = network_and_entity_kind(&hd.derivation_path())
+            else {
+                inconsistencies.push(FactorInstanceInconsistency::NotCAP26 { factor_instance });
+                continue;
+            };
+
+            let (expected_network_id, expected_entity_kind) =
+                *expected.get_or_insert((network_id, entity_kind));
+
+            if network_id != expected_network_id {
+                inconsistencies.push(FactorInstanceInconsistency::NetworkMismatch {
+                    factor_instance: factor_instance.clone(),
+                    expected: expected_network_id,
+                    found: network_id,
+                });
+            }
+            if entity_kind != expected_entity_kind {
+                inconsistencies.push(FactorInstanceInconsistency::EntityKindMismatch {
+                    factor_instance,
+                    expected: expected_entity_kind,
+                    found: entity_kind,
+                });
+            }
+        }
+
+        let lists = [
+            (
+                RoleKind::Primary,
+                FactorListKind::Threshold,
+                self.primary().get_threshold_factors(),
+                matrix_of_factor_source_ids
+                    .primary()
+                    .get_threshold_factors(),
+            ),
+            (
+                RoleKind::Primary,
+                FactorListKind::Override,
+                self.primary().get_override_factors(),
+                matrix_of_factor_source_ids.primary().get_override_factors(),
+            ),
+            (
+                RoleKind::Recovery,
+                FactorListKind::Threshold,
+                self.recovery().get_threshold_factors(),
+                matrix_of_factor_source_ids
+                    .recovery()
+                    .get_threshold_factors(),
+            ),
+            (
+                RoleKind::Recovery,
+                FactorListKind::Override,
+                self.recovery().get_override_factors(),
+                matrix_of_factor_source_ids
+                    .recovery()
+                    .get_override_factors(),
+            ),
+            (
+                RoleKind::Confirmation,
+                FactorListKind::Threshold,
+                self.confirmation().get_threshold_factors(),
+                matrix_of_factor_source_ids
+                    .confirmation()
+                    .get_threshold_factors(),
+            ),
+            (
+                RoleKind::Confirmation,
+                FactorListKind::Override,
+                self.confirmation().get_override_factors(),
+                matrix_of_factor_source_ids
+                    .confirmation()
+                    .get_override_factors(),
+            ),
+        ];
+
+        for (role, list, instances, factor_source_ids) in lists {
+            if instances.len() != factor_source_ids.len() {
+                inconsistencies.push(FactorInstanceInconsistency::NumberOfFactorsMismatch {
+                    role,
+                    list,
+                    expected: factor_source_ids.len(),
+                    found: instances.len(),
+                });
+            }
+            inconsistencies.extend(
+                instances
+                    .iter()
+                    .zip(factor_source_ids.iter())
+                    .enumerate()
+                    .filter(|(_, (instance, id))| instance.factor_source_id != **id)
+                    .map(|(index, (instance, id))| {
+                        FactorInstanceInconsistency::FactorSourceMismatch {
+                            role,
+                            list,
+                            index,
+                            expected: *id,
+                            found: instance.factor_source_id,
+                        }
+                    }),
+            );
+        }
+
+        MatrixOfFactorInstancesConsistencyReport {
+            network_id: expected.map(|(network_id, _)| network_id),
+            entity_kind: expected.map(|(_, entity_kind)| entity_kind),
+            inconsistencies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::upper_case_acronyms)]
+    type SUT = MatrixOfFactorInstancesConsistencyReport;
+
+    fn report(
+        matrix: &MatrixOfFactorInstances,
+        matrix_of_factor_source_ids: &MatrixOfFactorSourceIds,
+    ) -> SUT {
+        matrix.consistency_report(matrix_of_factor_source_ids)
+    }
+
+    /// `MatrixOfFactorInstances::sample` with its confirmation role replaced by
+    /// one with `factor_instance` as its only override factor, bypassing the
+    /// validation which rejects non securified instances.
+    fn sample_with_confirmation_override(
+        factor_instance: FactorInstance,
+    ) -> MatrixOfFactorInstances {
+        let confirmation_role = AbstractRoleBuilderOrBuilt::from_unvalidated(
+            serde_json::from_value(serde_json::json!({
+                "threshold": 0,
+                "thresholdFactors": [],
+                "overrideFactors": [factor_instance],
+            }))
+            .unwrap(),
+        );
+        MatrixOfFactorInstances {
+            confirmation_role,
+            ..MatrixOfFactorInstances::sample()
+        }
+    }
+
+    #[test]
+    fn sample_is_consistent() {
+        let sut = report(
+            &MatrixOfFactorInstances::sample(),
+            &MatrixOfFactorSourceIds::sample(),
+        );
+        assert!(sut.is_consistent());
+        assert_eq!(sut.network_id, Some(NetworkID::Mainnet));
+        assert_eq!(sut.entity_kind, Some(CAP26EntityKind::Account));
+    }
+
+    #[test]
+    fn identity_on_stokenet_is_consistent() {
+        let matrix = MatrixOfFactorInstances::from_matrix_of_sources_for_entity_kind_on_network(
+            MatrixOfFactorSources::sample(),
+            CAP26EntityKind::Identity,
+            NetworkID::Stokenet,
+        )
+        .unwrap();
+        let sut = report(&matrix, &MatrixOfFactorSourceIds::sample());
+        assert!(sut.is_consistent());
+        assert_eq!(sut.network_id, Some(NetworkID::Stokenet));
+        assert_eq!(sut.entity_kind, Some(CAP26EntityKind::Identity));
+    }
+
+    #[test]
+    fn other_matrix_of_factor_source_ids_is_inconsistent() {
+        let sut = report(
+            &MatrixOfFactorInstances::sample(),
+            &MatrixOfFactorSourceIds::sample_other(),
+        );
+        assert!(!sut.is_consistent());
+        assert!(sut.inconsistencies.iter().all(|i| matches!(
+            i,
+            FactorInstanceInconsistency::NumberOfFactorsMismatch { .. }
+                | FactorInstanceInconsistency::FactorSourceMismatch { .. }
+        )));
+    }
+
+    #[test]
+    fn unsecurified_instance_is_not_securified() {
+        let unsecurified = FactorInstance::from(
+            HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_10_unsecurified_at_index(0),
+        );
+        let sut = report(
+            &sample_with_confirmation_override(unsecurified.clone()),
+            &MatrixOfFactorSourceIds::sample(),
+        );
+        assert!(sut
+            .inconsistencies
+            .contains(&FactorInstanceInconsistency::NotSecurified {
+                factor_instance: unsecurified,
+                key_space: KeySpace::Unsecurified { is_hardened: true },
+            }));
+    }
+
+    #[test]
+    fn bip44_instance_is_not_cap26() {
+        let bip44 = FactorInstance::from(HierarchicalDeterministicFactorInstance::new(
+            FactorSourceIDFromHash::sample_device(),
+            sargon::HierarchicalDeterministicPublicKey::new(
+                sargon::PublicKey::sample(),
+                DerivationPath::Bip44Like {
+                    value: sargon::BIP44LikePath::sample(),
+                },
+            ),
+        ));
+        let sut = report(
+            &sample_with_confirmation_override(bip44.clone()),
+            &MatrixOfFactorSourceIds::sample(),
+        );
+        assert!(sut
+            .inconsistencies
+            .contains(&FactorInstanceInconsistency::NotCAP26 {
+                factor_instance: bip44,
+            }));
+        assert_eq!(sut.network_id, Some(NetworkID::Mainnet));
+        assert_eq!(sut.entity_kind, Some(CAP26EntityKind::Account));
+    }
+
+    #[test]
+    fn mixed_networks_and_entity_kinds() {
+        let account_mainnet = MatrixOfFactorInstances::sample();
+        let identity_stokenet =
+            MatrixOfFactorInstances::from_matrix_of_sources_for_entity_kind_on_network(
+                MatrixOfFactorSources::sample(),
+                CAP26EntityKind::Identity,
+                NetworkID::Stokenet,
+            )
+            .unwrap();
+        let mixed = MatrixOfFactorInstances {
+            built: PhantomData,
+            primary_role: account_mainnet.primary_role.clone(),
+            recovery_role: identity_stokenet.recovery_role.clone(),
+            confirmation_role: account_mainnet.confirmation_role.clone(),
+            number_of_days_until_auto_confirm: account_mainnet.number_of_days_until_auto_confirm,
+        };
+
+        let sut = report(&mixed, &MatrixOfFactorSourceIds::sample());
+        assert_eq!(sut.network_id, Some(NetworkID::Mainnet));
+        assert_eq!(sut.entity_kind, Some(CAP26EntityKind::Account));
+
+        let recovery_instances = identity_stokenet
+            .recovery()
+            .all_factors()
+            .into_iter()
+            .filter(|i| !account_mainnet.unique_factor_instances().contains(*i))
+            .cloned()
+            .collect::<IndexSet<_>>();
+        assert!(!recovery_instances.is_empty());
+
+        for factor_instance in recovery_instances {
+            assert!(sut
+                .inconsistencies
+                .contains(&FactorInstanceInconsistency::NetworkMismatch {
+                    factor_instance: factor_instance.clone(),
+                    expected: NetworkID::Mainnet,
+                    found: NetworkID::Stokenet,
+                }));
+            assert!(sut.inconsistencies.contains(
+                &FactorInstanceInconsistency::EntityKindMismatch {
+                    factor_instance,
+                    expected: CAP26EntityKind::Account,
+                    found: CAP26EntityKind::Identity,
+                }
+            ));
+        }
+        assert!(!sut.inconsistencies.iter().any(|i| matches!(
+            i,
+            FactorInstanceInconsistency::FactorSourceMismatch { .. }
+                | FactorInstanceInconsistency::NotSecurified { .. }
+        )));
+    }
+}
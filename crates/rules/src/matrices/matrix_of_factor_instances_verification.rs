@@ -0,0 +1,235 @@
+use crate::prelude::*;
+
+/// A lookup of the mnemonics of factor sources, e.g. backed by the secure
+/// storage of the host, used to verify FactorInstances by re-deriving them.
+pub trait MnemonicWithPassphraseLookup {
+    /// Returns the mnemonic of the factor source with `factor_source_id`, or
+    /// `None` if it is not known.
+    fn mnemonic_with_passphrase(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<Option<MnemonicWithPassphrase>, CommonError>;
+}
+
+impl MnemonicWithPassphraseLookup for IndexMap<FactorSourceIDFromHash, MnemonicWithPassphrase> {
+    fn mnemonic_with_passphrase(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<Option<MnemonicWithPassphrase>, CommonError> {
+        Ok(self.get(factor_source_id).cloned())
+    }
+}
+
+/// A FactorInstance of a `MatrixOfFactorInstances` which could not be verified
+/// against the mnemonic of its factor source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FactorInstanceVerificationFailure {
+    /// No mnemonic for the factor source of the instance was found in the lookup.
+    MissingMnemonic {
+        factor_instance: HierarchicalDeterministicFactorInstance,
+    },
+
+    /// Looking up the mnemonic for the factor source of the instance failed.
+    LookupFailed {
+        factor_instance: HierarchicalDeterministicFactorInstance,
+        error: CommonError,
+    },
+
+    /// Re-deriving the instance at its derivation path did not yield any
+    /// public key.
+    DerivationFailed {
+        factor_instance: HierarchicalDeterministicFactorInstance,
+    },
+
+    /// Re-deriving the instance at its derivation path using the mnemonic of
+    /// its factor source resulted in another public key, i.e. the instance is
+    /// corrupted or was swapped.
+    PublicKeyMismatch {
+        factor_instance: HierarchicalDeterministicFactorInstance,
+        derived: HierarchicalDeterministicFactorInstance,
+    },
+}
+
+/// The result of verifying a `MatrixOfFactorInstances` against mnemonics,
+/// see `MatrixOfFactorInstances::verify_against_mnemonics`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixOfFactorInstancesVerification {
+    /// HD instances for which the re-derived public key matched.
+    pub verified: IndexSet<HierarchicalDeterministicFactorInstance>,
+
+    /// Instances which cannot be verified by re-deriving them, e.g. physical
+    /// badges of address based factor sources.
+    pub unverifiable: IndexSet<FactorInstance>,
+
+    pub failures: Vec<FactorInstanceVerificationFailure>,
+}
+
+impl MatrixOfFactorInstancesVerification {
+    /// `true` if no instance failed verification, non HD instances are not
+    /// considered failures.
+    pub fn is_verified(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl MatrixOfFactorInstances {
+    /// Re-derives each `HierarchicalDeterministicFactorInstance` of this matrix at
+    /// its derivation path using the mnemonic of its factor source found in
+    /// `mnemonics`, and verifies that the public key matches.
+    pub fn verify_against_mnemonics(
+        &self,
+        mnemonics: &impl MnemonicWithPassphraseLookup,
+    ) -> MatrixOfFactorInstancesVerification {
+        let mut verified = IndexSet::new();
+        let mut unverifiable = IndexSet::new();
+        let mut failures = Vec::new();
+
+        for instance in self.unique_factor_instances() {
+            let (Ok(factor_source_id), Ok(factor_instance)) = (
+                FactorSourceIDFromHash::try_from(instance.factor_source_id),
+                HierarchicalDeterministicFactorInstance::try_from(instance.clone()),
+            ) else {
+                unverifiable.insert(instance);
+                continue;
+            };
+
+            let mnemonic_with_passphrase =
+                match mnemonics.mnemonic_with_passphrase(&factor_source_id) {
+                    Ok(Some(mnemonic_with_passphrase)) => mnemonic_with_passphrase,
+                    Ok(None) => {
+                        failures.push(FactorInstanceVerificationFailure::MissingMnemonic {
+                            factor_instance,
+                        });
+                        continue;
+                    }
+                    Err(error) => {
+                        failures.push(FactorInstanceVerificationFailure::LookupFailed {
+                            factor_instance,
+                            error,
+                        });
+                        continue;
+                    }
+                };
+
+            let Some(derived) = mnemonic_with_passphrase
+                .derive_public_keys(IndexSet::from_iter([factor_instance.derivation_path()]))
+                .into_iter()
+                .map(|public_key| {
+                    HierarchicalDeterministicFactorInstance::new(factor_source_id, public_key)
+                })
+                .next()
+            else {
+                failures
+                    .push(FactorInstanceVerificationFailure::DerivationFailed { factor_instance });
+                continue;
+            };
+
+            if derived == factor_instance {
+                verified.insert(factor_instance);
+            } else {
+                failures.push(FactorInstanceVerificationFailure::PublicKeyMismatch {
+                    factor_instance,
+                    derived,
+                });
+            }
+        }
+
+        MatrixOfFactorInstancesVerification {
+            verified,
+            unverifiable,
+            failures,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::upper_case_acronyms)]
+    type SUT = MatrixOfFactorInstancesVerification;
+
+    fn verify(matrix: &MatrixOfFactorInstances) -> SUT {
+        matrix.verify_against_mnemonics(&*MNEMONIC_BY_ID_MAP)
+    }
+
+    #[test]
+    fn sample_is_verified() {
+        let matrix = MatrixOfFactorInstances::sample();
+        let sut = verify(&matrix);
+        assert!(sut.is_verified());
+        assert!(sut.unverifiable.is_empty());
+        assert_eq!(sut.verified.len(), matrix.unique_factor_instances().len());
+    }
+
+    #[test]
+    fn identity_on_stokenet_is_verified() {
+        let matrix = MatrixOfFactorInstances::from_matrix_of_sources_for_entity_kind_on_network(
+            MatrixOfFactorSources::sample_other(),
+            CAP26EntityKind::Identity,
+            NetworkID::Stokenet,
+        )
+        .unwrap();
+        assert!(verify(&matrix).is_verified());
+    }
+
+    #[test]
+    fn missing_mnemonic() {
+        let matrix = MatrixOfFactorInstances::sample();
+        let sut = matrix
+            .verify_against_mnemonics(
+                &IndexMap::<FactorSourceIDFromHash, MnemonicWithPassphrase>::new(),
+            );
+        assert!(!sut.is_verified());
+        assert!(sut.verified.is_empty());
+        assert_eq!(sut.failures.len(), matrix.unique_factor_instances().len());
+        assert!(sut
+            .failures
+            .iter()
+            .all(|f| matches!(f, FactorInstanceVerificationFailure::MissingMnemonic { .. })));
+    }
+
+    #[test]
+    fn swapped_mnemonic_is_detected() {
+        let matrix = MatrixOfFactorInstances::sample();
+        let mut mnemonics = MNEMONIC_BY_ID_MAP.clone();
+        mnemonics.insert(
+            FactorSourceIDFromHash::sample_device(),
+            MnemonicWithPassphrase::sample_ledger(),
+        );
+        let sut = matrix.verify_against_mnemonics(&mnemonics);
+        assert!(!sut.is_verified());
+        assert!(sut.failures.iter().all(|f| matches!(
+            f,
+            FactorInstanceVerificationFailure::PublicKeyMismatch { factor_instance, .. }
+                if factor_instance.factor_source_id == FactorSourceIDFromHash::sample_device()
+        )));
+        assert!(!sut.failures.is_empty());
+    }
+
+    struct FailingLookup;
+    impl MnemonicWithPassphraseLookup for FailingLookup {
+        fn mnemonic_with_passphrase(
+            &self,
+            _factor_source_id: &FactorSourceIDFromHash,
+        ) -> Result<Option<MnemonicWithPassphrase>, CommonError> {
+            Err(CommonError::Unknown)
+        }
+    }
+
+    #[test]
+    fn lookup_failure_is_reported() {
+        let matrix = MatrixOfFactorInstances::sample();
+        let sut = matrix.verify_against_mnemonics(&FailingLookup);
+        assert!(!sut.is_verified());
+        assert!(sut.verified.is_empty());
+        assert_eq!(sut.failures.len(), matrix.unique_factor_instances().len());
+        assert!(sut.failures.iter().all(|f| matches!(
+            f,
+            FactorInstanceVerificationFailure::LookupFailed {
+                error: CommonError::Unknown,
+                ..
+            }
+        )));
+    }
+}
@@ -2,6 +2,14 @@ use crate::prelude::*;
 
 pub type MatrixOfFactorSourceIds = AbstractMatrixBuilt<FactorSourceID>;
 
+impl IsValidatedOnDeserialization for MatrixOfFactorSourceIds {
+    type Error = MatrixBuilderValidation;
+
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        self.validate()
+    }
+}
+
 #[cfg(test)]
 impl MatrixOfFactorSourceIds {
     pub(crate) fn with_roles_and_days(
@@ -36,6 +44,30 @@ impl MatrixOfFactorSourceIds {
     }
 }
 
+impl MatrixOfFactorSourceIds {
+    /// Validates this matrix using the same rules for each role in isolation and
+    /// for the roles in combination as `MatrixBuilder` enforces.
+    pub fn validate(&self) -> MatrixBuilderMutateResult {
+        MatrixBuilder::with_matrix(self.clone()).validate()
+    }
+
+    /// Deserializes a MatrixOfFactorSourceIds from `json` and validates it, like
+    /// `serde_json::from_str` does, but returning the validation error typed.
+    pub fn from_json_str(json: impl AsRef<str>) -> Result<Self, MatrixDeserializationError> {
+        let unvalidated = serde_json::from_str::<UnvalidatedMatrix<FactorSourceID>>(json.as_ref())?;
+        Self::validated(unvalidated)
+    }
+
+    /// Validates `unvalidated`, returning the matrix if it is valid.
+    pub(crate) fn validated(
+        unvalidated: UnvalidatedMatrix<FactorSourceID>,
+    ) -> Result<Self, MatrixDeserializationError> {
+        let matrix = Self::from_unvalidated(unvalidated);
+        matrix.validate()?;
+        Ok(matrix)
+    }
+}
+
 impl MatrixOfFactorSourceIds {
     fn sample_from_template(template: MatrixTemplate) -> Self {
         template
@@ -131,6 +163,124 @@ mod tests {
     #[test]
     fn template() {}
 
+    fn json_with(mutate: impl FnOnce(&mut serde_json::Value)) -> String {
+        let mut json = serde_json::to_value(SUT::sample()).unwrap();
+        mutate(&mut json);
+        json.to_string()
+    }
+
+    #[test]
+    fn from_json_str_valid() {
+        assert_eq!(SUT::from_json_str(json_with(|_| {})), Ok(SUT::sample()));
+    }
+
+    #[test]
+    fn from_json_str_all_samples_are_valid() {
+        MatrixTemplatePreset::all()
+            .into_iter()
+            .map(|p| {
+                p.template()
+                    .materialize(*ALL_FACTOR_SOURCE_ID_SAMPLES_INC_NON_HD)
+                    .unwrap()
+            })
+            .for_each(|m| {
+                let json = serde_json::to_string(&m).unwrap();
+                assert_eq!(SUT::from_json_str(json), Ok(m));
+            });
+    }
+
+    #[test]
+    fn from_json_str_invalid_json() {
+        assert!(matches!(
+            SUT::from_json_str("{}"),
+            Err(MatrixDeserializationError::InvalidJson {
+                category: serde_json::error::Category::Data,
+                line: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn serde_json_from_str_password_in_primary_override_is_err() {
+        let json = json_with(|json| {
+            json["primaryRole"]["overrideFactors"] =
+                serde_json::json!([FactorSourceID::sample_password()]);
+        });
+        assert!(serde_json::from_str::<SUT>(&json).is_err());
+        assert!(serde_json::from_str::<Vec<SUT>>(&format!("[{}]", json)).is_err());
+    }
+
+    #[test]
+    fn serde_json_from_str_valid() {
+        let json = serde_json::to_string(&vec![SUT::sample(), SUT::sample_other()]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<SUT>>(&json).unwrap(),
+            vec![SUT::sample(), SUT::sample_other()]
+        );
+    }
+
+    #[test]
+    fn from_json_str_password_in_primary_override() {
+        let json = json_with(|json| {
+            json["primaryRole"]["overrideFactors"] =
+                serde_json::json!([FactorSourceID::sample_password()]);
+        });
+        assert_eq!(
+            SUT::from_json_str(json),
+            Err(MatrixDeserializationError::InvalidMatrix(
+                MatrixBuilderValidation::RoleInIsolation {
+                    role: RoleKind::Primary,
+                    violation: RoleBuilderValidation::ForeverInvalid(
+                        ForeverInvalidReason::PrimaryCannotHavePasswordInOverrideList
+                    )
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn from_json_str_recovery_and_confirmation_overlap() {
+        let json = json_with(|json| {
+            json["confirmationRole"]["overrideFactors"] =
+                serde_json::json!([FactorSourceID::sample_ledger()]);
+        });
+        assert_eq!(
+            SUT::from_json_str(json),
+            Err(MatrixDeserializationError::InvalidMatrix(
+                MatrixBuilderValidation::CombinationViolation(
+                    MatrixRolesInCombinationViolation::ForeverInvalid(
+                        MatrixRolesInCombinationForeverInvalid::RecoveryAndConfirmationFactorsOverlap
+                    )
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn from_json_str_threshold_zero() {
+        let json = json_with(|json| {
+            json["primaryRole"]["threshold"] = serde_json::json!(0);
+        });
+        assert!(matches!(
+            SUT::from_json_str(json),
+            Err(MatrixDeserializationError::InvalidMatrix(
+                MatrixBuilderValidation::RoleInIsolation {
+                    role: RoleKind::Primary,
+                    ..
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn from_json_str_zero_days_until_auto_confirm() {
+        let json = json_with(|json| {
+            json["numberOfDaysUntilAutoConfirm"] = serde_json::json!(0);
+        });
+        assert!(SUT::from_json_str(json).is_err());
+    }
+
     #[test]
     fn equality() {
         assert_eq!(SUT::sample(), SUT::sample());
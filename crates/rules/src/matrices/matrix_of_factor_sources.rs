@@ -2,6 +2,22 @@ use crate::prelude::*;
 
 pub type MatrixOfFactorSources = AbstractMatrixBuilt<FactorSource>;
 
+impl IsValidatedOnDeserialization for MatrixOfFactorSources {
+    type Error = MatrixBuilderValidation;
+
+    /// Validates the matrix of the ids of the factor sources.
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        MatrixOfFactorSourceIds {
+            built: PhantomData,
+            primary_role: self.primary_role.factor_source_ids(),
+            recovery_role: self.recovery_role.factor_source_ids(),
+            confirmation_role: self.confirmation_role.factor_source_ids(),
+            number_of_days_until_auto_confirm: self.number_of_days_until_auto_confirm,
+        }
+        .validate()
+    }
+}
+
 impl MatrixOfFactorSources {
     pub fn new(
         matrix: MatrixOfFactorSourceIds,
@@ -1,6 +1,8 @@
 mod abstract_matrix_builder_or_built;
 mod builder;
 mod matrix_of_factor_instances;
+mod matrix_of_factor_instances_consistency;
+mod matrix_of_factor_instances_verification;
 mod matrix_of_factor_source_ids;
 mod matrix_of_factor_sources;
 
@@ -8,5 +10,7 @@ pub(crate) use abstract_matrix_builder_or_built::*;
 #[allow(unused_imports)]
 pub use builder::*;
 pub use matrix_of_factor_instances::*;
+pub use matrix_of_factor_instances_consistency::*;
+pub use matrix_of_factor_instances_verification::*;
 pub use matrix_of_factor_source_ids::*;
 pub use matrix_of_factor_sources::*;
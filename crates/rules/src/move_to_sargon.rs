@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
 /// A kind of factor list, either threshold, or override kind.
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum FactorListKind {
     Threshold,
     Override,
@@ -24,6 +24,36 @@ impl HasFactorSourceKindObjectSafe for FactorSourceID {
     }
 }
 
+/// Types which know the kind of entity they belong to, e.g. the address of
+/// either an account or a persona.
+pub trait HasEntityKindObjectSafe {
+    fn get_entity_kind(&self) -> CAP26EntityKind;
+}
+impl HasEntityKindObjectSafe for AddressOfAccountOrPersona {
+    fn get_entity_kind(&self) -> CAP26EntityKind {
+        match self {
+            AddressOfAccountOrPersona::Account(_) => CAP26EntityKind::Account,
+            AddressOfAccountOrPersona::Identity(_) => CAP26EntityKind::Identity,
+        }
+    }
+}
+
+/// Types which can name the `DerivationPreset` used for the securified (MFA)
+/// keys of some kind of entity.
+pub trait HasMfaDerivationPreset {
+    /// The `DerivationPreset` used for securified (MFA) keys of entities of
+    /// kind `entity_kind`, i.e. `AccountMfa` or `IdentityMfa`.
+    fn mfa_for_entity_kind(entity_kind: CAP26EntityKind) -> Self;
+}
+impl HasMfaDerivationPreset for DerivationPreset {
+    fn mfa_for_entity_kind(entity_kind: CAP26EntityKind) -> Self {
+        match entity_kind {
+            CAP26EntityKind::Account => DerivationPreset::AccountMfa,
+            CAP26EntityKind::Identity => DerivationPreset::IdentityMfa,
+        }
+    }
+}
+
 #[allow(dead_code)]
 // TODO REMOVE once migrated to sargon
 pub trait SampleValues: Sized {
@@ -97,7 +127,7 @@ impl SampleValues for FactorSourceID {
 use assert_json_diff::assert_json_include;
 use core::fmt::Debug;
 use pretty_assertions::assert_eq;
-use sargon::{DerivationPath, FactorInstancesCache, NetworkID, NextDerivationEntityIndexAssigner};
+use sargon::{DerivationPath, FactorInstancesCache, NextDerivationEntityIndexAssigner};
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::str::FromStr;
@@ -311,6 +341,26 @@ pub trait MnemonicWithPassphraseSamples: Sized {
         ]
     }
 
+    /// Derives `quantity_per_factor` many securified (MFA) FactorInstances for
+    /// entities of kind `entity_kind` on `network_id` for each of `sources`.
+    fn derive_mfa_instances_for_factor_sources(
+        network_id: NetworkID,
+        entity_kind: CAP26EntityKind,
+        quantity_per_factor: usize,
+        sources: impl IntoIterator<Item = FactorSource>,
+    ) -> IndexMap<FactorSourceIDFromHash, FactorInstances> {
+        Self::derive_instances_for_factor_sources(
+            network_id,
+            quantity_per_factor,
+            [DerivationPreset::mfa_for_entity_kind(entity_kind)],
+            sources,
+        )
+    }
+
+    /// Derives `quantity_per_factor` many FactorInstances for each of the
+    /// `derivation_presets` for each hash based factor source of `sources`,
+    /// address based factor sources, e.g. trusted contacts, are skipped since
+    /// they have no mnemonic.
     fn derive_instances_for_factor_sources(
         network_id: NetworkID,
         quantity_per_factor: usize,
@@ -327,8 +377,11 @@ pub trait MnemonicWithPassphraseSamples: Sized {
 
         sources
             .into_iter()
-            .map(|fs| {
-                let fsid = fs.id_from_hash();
+            .filter_map(|fs| match fs.id() {
+                FactorSourceID::Hash { value } => Some(value),
+                FactorSourceID::Address { .. } => None,
+            })
+            .map(|fsid| {
                 let mwp = fsid.sample_associated_mnemonic();
 
                 let paths = derivation_presets
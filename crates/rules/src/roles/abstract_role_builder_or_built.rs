@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::prelude::*;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AbstractRoleBuilderOrBuilt<const R: u8, F, T> {
     #[serde(skip)]
@@ -19,48 +19,140 @@ pub struct AbstractRoleBuilderOrBuilt<const R: u8, F, T> {
 pub(crate) type AbstractBuiltRoleWithFactor<const R: u8, F> = AbstractRoleBuilderOrBuilt<R, F, ()>;
 pub(crate) type RoleBuilder<const R: u8> = AbstractRoleBuilderOrBuilt<R, FactorSourceID, Built>;
 
+/// Validation run when deserializing roles and matrices, so that a role or
+/// matrix which could not have been constructed cannot be deserialized either,
+/// be it on its own or as part of some containing type, e.g. a shield.
+pub trait IsValidatedOnDeserialization {
+    type Error: std::fmt::Display;
+
+    fn validate_deserialized(&self) -> Result<(), Self::Error>;
+}
+
+/// The serialized form of an `AbstractRoleBuilderOrBuilt`, deserialized without
+/// any validation.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UnvalidatedRole<F> {
+    threshold: u8,
+    threshold_factors: Vec<F>,
+    override_factors: Vec<F>,
+}
+
+impl<const R: u8, F, T> AbstractRoleBuilderOrBuilt<R, F, T> {
+    /// N.B. `unvalidated` is not validated, use `validate_deserialized`.
+    pub(crate) fn from_unvalidated(unvalidated: UnvalidatedRole<F>) -> Self {
+        Self {
+            built: PhantomData,
+            threshold: unvalidated.threshold,
+            threshold_factors: unvalidated.threshold_factors,
+            override_factors: unvalidated.override_factors,
+        }
+    }
+}
+
+impl<'de, const R: u8, F: Deserialize<'de>, T> Deserialize<'de>
+    for AbstractRoleBuilderOrBuilt<R, F, T>
+where
+    Self: IsValidatedOnDeserialization,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let role = Self::from_unvalidated(UnvalidatedRole::deserialize(deserializer)?);
+        role.validate_deserialized()
+            .map_err(serde::de::Error::custom)?;
+        Ok(role)
+    }
+}
+
+/// Returns an error naming the first of `factors` which is not in the
+/// `Securified` key space, if any.
+///
+/// Non HD instances, e.g. physical badges, are not aware of any key space and
+/// are thus always valid.
+pub(crate) fn validate_factors_are_securified(
+    role: RoleKind,
+    list: FactorListKind,
+    factors: &[FactorInstance],
+) -> Result<(), FactorNotSecurified> {
+    factors
+        .iter()
+        .find_map(|f| {
+            let key_space = f
+                .maybe_key_space()
+                .filter(|key_space| *key_space != KeySpace::Securified)?;
+            let factor_instance =
+                HierarchicalDeterministicFactorInstance::try_from_factor_instance(f.clone())
+                    .ok()?;
+            Some(FactorNotSecurified {
+                role,
+                list,
+                key_space,
+                factor_instance,
+            })
+        })
+        .map_or(Ok(()), Err)
+}
+
+/// Factors which are not aware of any key space, e.g. FactorSourceIDs, which
+/// are always valid to use in a role, as opposed to FactorInstances which must be
+/// securified.
+pub(crate) trait IsKeySpaceAgnostic {}
+impl IsKeySpaceAgnostic for FactorSourceID {}
+impl IsKeySpaceAgnostic for FactorSource {}
+impl IsKeySpaceAgnostic for FactorSourceTemplate {}
+
 impl<const R: u8, F: IsMaybeKeySpaceAware, T> AbstractRoleBuilderOrBuilt<R, F, T> {
     pub fn role(&self) -> RoleKind {
         RoleKind::from_u8(R).expect("RoleKind should be valid")
     }
 
-    pub(crate) fn with_factors(
+    fn with_factors_unchecked(
         threshold: u8,
         threshold_factors: impl IntoIterator<Item = F>,
         override_factors: impl IntoIterator<Item = F>,
     ) -> Self {
-        let assert_is_securified = |factors: &Vec<F>| -> Result<(), CommonError> {
-            let trait_objects: Vec<&dyn IsMaybeKeySpaceAware> = factors
-                .iter()
-                .map(|x| x as &dyn IsMaybeKeySpaceAware)
-                .collect();
-            if trait_objects
-                .iter()
-                .filter_map(|x| x.maybe_key_space())
-                .any(|x| x != KeySpace::Securified)
-            {
-                return Err(crate::CommonError::IndexUnsecurifiedExpectedSecurified);
-            }
-            Ok(())
-        };
-
-        let threshold_factors = threshold_factors.into_iter().collect();
-        let override_factors = override_factors.into_iter().collect();
-
-        assert_is_securified(&threshold_factors)
-            .expect("Should not have allowed building of invalid Role");
-        assert_is_securified(&override_factors)
-            .expect("Should not have allowed building of invalid Role");
-
         Self {
             built: PhantomData,
             threshold,
-            threshold_factors,
-            override_factors,
+            threshold_factors: threshold_factors.into_iter().collect(),
+            override_factors: override_factors.into_iter().collect(),
         }
     }
 }
 
+impl<const R: u8, T> AbstractRoleBuilderOrBuilt<R, FactorInstance, T> {
+    /// Creates a role with the factors, validating that each factor is in the
+    /// `Securified` key space, if it is key space aware.
+    pub(crate) fn try_with_factors(
+        threshold: u8,
+        threshold_factors: impl IntoIterator<Item = FactorInstance>,
+        override_factors: impl IntoIterator<Item = FactorInstance>,
+    ) -> Result<Self, FactorNotSecurified> {
+        let sut = Self::with_factors_unchecked(threshold, threshold_factors, override_factors);
+        sut.validate_securified()?;
+        Ok(sut)
+    }
+
+    /// Validates that each factor is in the `Securified` key space, if it is
+    /// key space aware.
+    pub(crate) fn validate_securified(&self) -> Result<(), FactorNotSecurified> {
+        let role = self.role();
+        validate_factors_are_securified(role, FactorListKind::Threshold, &self.threshold_factors)?;
+        validate_factors_are_securified(role, FactorListKind::Override, &self.override_factors)
+    }
+}
+
+impl<const R: u8, F: IsMaybeKeySpaceAware + IsKeySpaceAgnostic, T>
+    AbstractRoleBuilderOrBuilt<R, F, T>
+{
+    pub(crate) fn with_factors(
+        threshold: u8,
+        threshold_factors: impl IntoIterator<Item = F>,
+        override_factors: impl IntoIterator<Item = F>,
+    ) -> Self {
+        Self::with_factors_unchecked(threshold, threshold_factors, override_factors)
+    }
+}
+
 impl<const R: u8, F, T> AbstractRoleBuilderOrBuilt<R, F, T> {
     pub fn all_factors(&self) -> Vec<&F> {
         self.threshold_factors
@@ -111,6 +203,17 @@ impl<const R: u8> RoleBuilder<R> {
         }
     }
 
+    /// Creates a builder with the threshold and factors of an already built
+    /// role, e.g. for editing it.
+    pub(crate) fn from_built(built: RoleWithFactorSourceIds<R>) -> Self {
+        Self {
+            built: PhantomData,
+            threshold: built.threshold,
+            threshold_factors: built.threshold_factors,
+            override_factors: built.override_factors,
+        }
+    }
+
     pub(crate) fn mut_threshold_factors(&mut self) -> &mut Vec<FactorSourceID> {
         &mut self.threshold_factors
     }
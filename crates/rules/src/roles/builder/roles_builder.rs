@@ -6,6 +6,15 @@ pub type PrimaryRoleBuilder = RoleBuilder<{ ROLE_PRIMARY }>;
 pub type RecoveryRoleBuilder = RoleBuilder<{ ROLE_RECOVERY }>;
 pub type ConfirmationRoleBuilder = RoleBuilder<{ ROLE_CONFIRMATION }>;
 
+impl<const R: u8> IsValidatedOnDeserialization for RoleBuilder<R> {
+    type Error = std::convert::Infallible;
+
+    /// A builder is allowed to be invalid, it is validated when built.
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 impl PrimaryRoleWithFactorSourceIds {
     pub(crate) fn primary_with_factors(
@@ -172,6 +181,26 @@ impl FactorSourceInRoleBuilderValidationStatus {
     }
 }
 
+/// A violation of the rules of a `RoleBuilder`, together with the FactorSourceIDs
+/// causing it - which might be empty, e.g. if the role has no factors at all.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RoleBuilderViolation {
+    pub violation: RoleBuilderValidation,
+    pub factor_source_ids: Vec<FactorSourceID>,
+}
+
+impl RoleBuilderViolation {
+    pub(crate) fn new(
+        violation: RoleBuilderValidation,
+        factor_source_ids: impl IntoIterator<Item = FactorSourceID>,
+    ) -> Self {
+        Self {
+            violation,
+            factor_source_ids: factor_source_ids.into_iter().collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 impl FactorSourceInRoleBuilderValidationStatus {
     pub(crate) fn ok(role: RoleKind, factor_source_id: FactorSourceID) -> Self {
@@ -384,55 +413,66 @@ impl<const R: u8> RoleBuilder<R> {
     /// Validates `self` by "replaying" the addition of each factor source in `self` to a
     /// "simulation" (clone). If the simulation is valid, then `self` is valid.
     pub(crate) fn validate(&self) -> RoleBuilderMutateResult {
+        self.violations()
+            .into_iter()
+            .next()
+            .map_or(Ok(()), |v| Err(v.violation))
+    }
+
+    /// Like `validate` but does not stop at the first violation, instead every
+    /// violation is collected, in the same order `validate` would find them, i.e.
+    /// the first violation is the one `validate` returns.
+    pub(crate) fn violations(&self) -> Vec<RoleBuilderViolation> {
+        let mut violations = Vec::new();
         let mut simulation = Self::new();
 
         // Validate override factors
         for f in self.get_override_factors() {
-            let validation = simulation.add_factor_source_to_override(*f);
-            match validation.as_ref() {
+            match simulation.add_factor_source_to_override(*f) {
                 Ok(()) | Err(NotYetValid(_)) => continue,
-                Err(ForeverInvalid(_)) | Err(BasicViolation(_)) => return validation,
+                Err(violation) => violations.push(RoleBuilderViolation::new(violation, [*f])),
             }
         }
 
         // Validate threshold factors
         for f in self.get_threshold_factors() {
-            let validation = simulation._add_factor_source_to_list(*f, Threshold);
-            match validation.as_ref() {
+            match simulation._add_factor_source_to_list(*f, Threshold) {
                 Ok(()) | Err(NotYetValid(_)) => continue,
-                Err(ForeverInvalid(_)) | Err(BasicViolation(_)) => return validation,
+                Err(violation) => violations.push(RoleBuilderViolation::new(violation, [*f])),
             }
         }
 
         // Validate threshold count
         if self.role() == RoleKind::Primary {
             if self.get_threshold_factors().len() < self.get_threshold() as usize {
-                return RoleBuilderMutateResult::not_yet_valid(
-                    NotYetValidReason::ThresholdHigherThanThresholdFactorsLen,
-                );
+                violations.push(RoleBuilderViolation::new(
+                    NotYetValid(NotYetValidReason::ThresholdHigherThanThresholdFactorsLen),
+                    self.get_threshold_factors().clone(),
+                ));
             }
             if self.get_threshold() == 0 && !self.get_threshold_factors().is_empty() {
-                return RoleBuilderMutateResult::not_yet_valid(
-                    NotYetValidReason::PrimaryRoleWithThresholdCannotBeZeroWithFactors,
-                );
+                violations.push(RoleBuilderViolation::new(
+                    NotYetValid(NotYetValidReason::PrimaryRoleWithThresholdCannotBeZeroWithFactors),
+                    self.get_threshold_factors().clone(),
+                ));
             }
         } else if self.get_threshold() != 0 {
-            match self.role() {
+            let violation = match self.role() {
                 Primary => unreachable!("Primary role should have been handled earlier"),
-                Recovery => {
-                    return RoleBuilderMutateResult::basic_violation(RecoveryCannotSetThreshold)
-                }
-                Confirmation => {
-                    return RoleBuilderMutateResult::basic_violation(ConfirmationCannotSetThreshold)
-                }
-            }
+                Recovery => BasicViolation(RecoveryCannotSetThreshold),
+                Confirmation => BasicViolation(ConfirmationCannotSetThreshold),
+            };
+            violations.push(RoleBuilderViolation::new(violation, []));
         }
 
         if self.all_factors().is_empty() {
-            return RoleBuilderMutateResult::not_yet_valid(RoleMustHaveAtLeastOneFactor);
+            violations.push(RoleBuilderViolation::new(
+                NotYetValid(RoleMustHaveAtLeastOneFactor),
+                [],
+            ));
         }
 
-        Ok(())
+        violations
     }
 
     fn validation_for_addition_of_factor_source_of_kind_to_override_for_non_primary_role(
@@ -764,6 +804,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn violations_empty_is_single_not_yet_valid() {
+        let sut = RecoveryRoleBuilder::new();
+        assert_eq!(
+            sut.violations(),
+            vec![RoleBuilderViolation::new(
+                NotYetValid(RoleMustHaveAtLeastOneFactor),
+                []
+            )]
+        );
+    }
+
+    #[test]
+    fn violations_collects_all_and_first_is_validate() {
+        let mut sut = PrimaryRoleBuilder::new();
+        sut.unchecked_add_factor_source_to_list(FactorSourceID::sample_device(), Override);
+        sut.unchecked_add_factor_source_to_list(FactorSourceID::sample_password(), Override);
+        sut.unchecked_add_factor_source_to_list(FactorSourceID::sample_device_other(), Threshold);
+        sut.unchecked_set_threshold(2);
+
+        let violations = sut.violations();
+        pretty_assertions::assert_eq!(
+            violations,
+            vec![
+                RoleBuilderViolation::new(
+                    ForeverInvalid(PrimaryCannotHavePasswordInOverrideList),
+                    [FactorSourceID::sample_password()]
+                ),
+                RoleBuilderViolation::new(
+                    ForeverInvalid(PrimaryCannotHaveMultipleDevices),
+                    [FactorSourceID::sample_device_other()]
+                ),
+                RoleBuilderViolation::new(
+                    NotYetValid(ThresholdHigherThanThresholdFactorsLen),
+                    [FactorSourceID::sample_device_other()]
+                ),
+            ]
+        );
+        assert_eq!(sut.validate(), Err(violations[0].violation));
+    }
+
     #[test]
     fn confirmation_validation_add_is_err_for_threshold() {
         let sut = ConfirmationRoleBuilder::new();
@@ -11,30 +11,54 @@ pub struct GeneralRoleWithHierarchicalDeterministicFactorInstances {
     override_factors: Vec<HierarchicalDeterministicFactorInstance>,
 }
 
+/// Construction of a `GeneralRoleWithHierarchicalDeterministicFactorInstances`
+/// failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GeneralRoleWithFactorInstancesError {
+    #[error(transparent)]
+    FactorNotSecurified(#[from] FactorNotSecurified),
+
+    /// The factors violate the rules of `role`, the same rules `RoleBuilder`
+    /// enforces.
+    #[error("Role {role:?} is invalid: {violation}")]
+    InvalidRole {
+        role: RoleKind,
+        violation: RoleBuilderValidation,
+    },
+
+    /// A factor instance of `role` is not hierarchical deterministic, e.g. a
+    /// physical badge.
+    #[error(
+        "Factor instance in role {role:?} is not hierarchical deterministic: {factor_instance:?}"
+    )]
+    NotHierarchicalDeterministic {
+        role: RoleKind,
+        factor_instance: FactorInstance,
+    },
+}
+
 impl GeneralRoleWithHierarchicalDeterministicFactorInstances {
+    /// # Throws
+    /// If any of the factors is not in the `Securified` key space, naming the
+    /// offending factor instance, or if the factors violate the rules of `role`.
     pub fn with_factors_and_role(
         role: RoleKind,
         threshold_factors: impl IntoIterator<Item = HierarchicalDeterministicFactorInstance>,
         threshold: u8,
         override_factors: impl IntoIterator<Item = HierarchicalDeterministicFactorInstance>,
-    ) -> Result<Self, CommonError> {
+    ) -> Result<Self, GeneralRoleWithFactorInstancesError> {
         let threshold_factors = threshold_factors.into_iter().collect_vec();
         let override_factors = override_factors.into_iter().collect_vec();
 
         // validate
-        let _ = PrimaryRoleWithFactorInstances::with_factors(
-            threshold,
-            threshold_factors
-                .clone()
-                .into_iter()
-                .map(FactorInstance::from)
-                .collect_vec(),
-            override_factors
-                .clone()
-                .into_iter()
-                .map(FactorInstance::from)
-                .collect_vec(),
-        );
+        let instances = |xs: &Vec<HierarchicalDeterministicFactorInstance>| {
+            xs.iter().cloned().map(FactorInstance::from).collect_vec()
+        };
+        let threshold_instances = instances(&threshold_factors);
+        let override_instances = instances(&override_factors);
+        validate_factors_are_securified(role, FactorListKind::Threshold, &threshold_instances)?;
+        validate_factors_are_securified(role, FactorListKind::Override, &override_instances)?;
+        Self::validate_role(role, threshold, &threshold_instances, &override_instances)?;
 
         Ok(Self {
             role,
@@ -43,6 +67,36 @@ impl GeneralRoleWithHierarchicalDeterministicFactorInstances {
             override_factors,
         })
     }
+
+    /// Validates the factor sources of the instances using the same rules as
+    /// the `RoleBuilder` of `role`.
+    fn validate_role(
+        role: RoleKind,
+        threshold: u8,
+        threshold_factors: &[FactorInstance],
+        override_factors: &[FactorInstance],
+    ) -> Result<(), GeneralRoleWithFactorInstancesError> {
+        let ids = |xs: &[FactorInstance]| xs.iter().map(|f| f.factor_source_id).collect_vec();
+        let threshold_factors = ids(threshold_factors);
+        let override_factors = ids(override_factors);
+        match role {
+            RoleKind::Primary => {
+                PrimaryRoleBuilder::with_factors(threshold, threshold_factors, override_factors)
+                    .validate()
+            }
+            RoleKind::Recovery => {
+                RecoveryRoleBuilder::with_factors(threshold, threshold_factors, override_factors)
+                    .validate()
+            }
+            RoleKind::Confirmation => ConfirmationRoleBuilder::with_factors(
+                threshold,
+                threshold_factors,
+                override_factors,
+            )
+            .validate(),
+        }
+        .map_err(|violation| GeneralRoleWithFactorInstancesError::InvalidRole { role, violation })
+    }
 }
 
 impl HasRoleKindObjectSafe for GeneralRoleWithHierarchicalDeterministicFactorInstances {
@@ -54,11 +108,11 @@ impl HasRoleKindObjectSafe for GeneralRoleWithHierarchicalDeterministicFactorIns
 impl TryFrom<(MatrixOfFactorInstances, RoleKind)>
     for GeneralRoleWithHierarchicalDeterministicFactorInstances
 {
-    type Error = CommonError;
+    type Error = GeneralRoleWithFactorInstancesError;
 
     fn try_from(
         (matrix, role_kind): (MatrixOfFactorInstances, RoleKind),
-    ) -> Result<Self, CommonError> {
+    ) -> Result<Self, GeneralRoleWithFactorInstancesError> {
         let threshold_factors: Vec<FactorInstance>;
         let override_factors: Vec<FactorInstance>;
         let threshold: u8;
@@ -84,42 +138,47 @@ impl TryFrom<(MatrixOfFactorInstances, RoleKind)>
             }
         }
 
-        Self::with_factors_and_role(
-            role_kind,
-            threshold_factors
-                .iter()
+        let hd = |xs: Vec<FactorInstance>| {
+            xs.into_iter()
                 .map(|f| {
                     HierarchicalDeterministicFactorInstance::try_from_factor_instance(f.clone())
+                        .map_err(|_| {
+                            GeneralRoleWithFactorInstancesError::NotHierarchicalDeterministic {
+                                role: role_kind,
+                                factor_instance: f,
+                            }
+                        })
                 })
-                .collect::<Result<Vec<HierarchicalDeterministicFactorInstance>, CommonError>>()?,
+                .collect::<Result<Vec<HierarchicalDeterministicFactorInstance>, _>>()
+        };
+
+        Self::with_factors_and_role(
+            role_kind,
+            hd(threshold_factors)?,
             threshold,
-            override_factors
-                .iter()
-                .map(|f| {
-                    HierarchicalDeterministicFactorInstance::try_from_factor_instance(f.clone())
-                })
-                .collect::<Result<Vec<HierarchicalDeterministicFactorInstance>, CommonError>>()?,
+            hd(override_factors)?,
         )
     }
 }
 
 impl GeneralRoleWithHierarchicalDeterministicFactorInstances {
+    /// # Throws
+    /// If `factor` is not in the `Securified` key space.
     pub fn single_override(
         role: RoleKind,
         factor: HierarchicalDeterministicFactorInstance,
-    ) -> Self {
-        assert!(factor.is_securified(), "non securified factor");
+    ) -> Result<Self, GeneralRoleWithFactorInstancesError> {
         Self::with_factors_and_role(role, [], 0, [factor])
-            .expect("Zero threshold with zero threshold factors and one override should not fail.")
     }
 
+    /// # Throws
+    /// If `factor` is not in the `Securified` key space, or if `role` does not
+    /// support threshold factors, i.e. is not Primary.
     pub fn single_threshold(
         role: RoleKind,
         factor: HierarchicalDeterministicFactorInstance,
-    ) -> Self {
-        assert!(factor.is_securified(), "non securified factor");
+    ) -> Result<Self, GeneralRoleWithFactorInstancesError> {
         Self::with_factors_and_role(role, [factor], 1, [])
-            .expect("Single threshold with one threshold factor should not fail.")
     }
 }
 
@@ -178,7 +237,7 @@ mod test {
     #[test]
     fn test_single_threshold() {
         pretty_assertions::assert_eq!(
-            SUT::single_threshold(RoleKind::Primary,  HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_1_securified_at_index(0)),
+            SUT::single_threshold(RoleKind::Primary,  HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_1_securified_at_index(0)).unwrap(),
             SUT::with_factors_and_role(
                 RoleKind::Primary,
                 [
@@ -196,7 +255,7 @@ mod test {
             let sut = SUT::single_override(
                 role,
                 HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_0_securified_at_index(0)
-            );
+            ).unwrap();
             assert_eq!(sut.get_role_kind(), role);
         };
         test(RoleKind::Primary);
@@ -251,14 +310,123 @@ mod test {
     }
 
     #[test]
-    fn test_from_matrix_containing_physical_badge() {
+    fn test_single_override_unsecurified_is_err() {
+        let instance = HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_10_unsecurified_at_index(0);
+        let err = SUT::single_override(RoleKind::Recovery, instance.clone()).unwrap_err();
+        let GeneralRoleWithFactorInstancesError::FactorNotSecurified(err) = err else {
+            panic!("Expected FactorNotSecurified, got: {:?}", err);
+        };
+        assert_eq!(err.role, RoleKind::Recovery);
+        assert_eq!(err.list, FactorListKind::Override);
+        assert_eq!(err.factor_instance, instance);
+    }
+
+    #[test]
+    fn test_single_threshold_unsecurified_is_err() {
+        let res = SUT::single_threshold(
+            RoleKind::Primary,
+            HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_10_unsecurified_at_index(0),
+        );
+        assert!(matches!(
+            res,
+            Err(GeneralRoleWithFactorInstancesError::FactorNotSecurified(
+                FactorNotSecurified {
+                    role: RoleKind::Primary,
+                    list: FactorListKind::Threshold,
+                    ..
+                }
+            ))
+        ));
+    }
+
+    fn securified(index: u32) -> HierarchicalDeterministicFactorInstance {
+        HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_0_securified_at_index(index)
+    }
+
+    #[test]
+    fn test_single_threshold_recovery_is_err() {
+        assert!(matches!(
+            SUT::single_threshold(RoleKind::Recovery, securified(0)),
+            Err(GeneralRoleWithFactorInstancesError::InvalidRole {
+                role: RoleKind::Recovery,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_confirmation_with_threshold_factors_is_err() {
+        assert!(matches!(
+            SUT::with_factors_and_role(RoleKind::Confirmation, [securified(0)], 0, []),
+            Err(GeneralRoleWithFactorInstancesError::InvalidRole {
+                role: RoleKind::Confirmation,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_primary_threshold_higher_than_factors_is_err() {
+        assert_eq!(
+            SUT::with_factors_and_role(RoleKind::Primary, [securified(0)], 2, []),
+            Err(GeneralRoleWithFactorInstancesError::InvalidRole {
+                role: RoleKind::Primary,
+                violation: RoleBuilderValidation::NotYetValid(
+                    NotYetValidReason::ThresholdHigherThanThresholdFactorsLen
+                )
+            })
+        );
+    }
+
+    #[test]
+    fn test_empty_role_is_err() {
+        assert!(matches!(
+            SUT::with_factors_and_role(RoleKind::Recovery, [], 0, []),
+            Err(GeneralRoleWithFactorInstancesError::InvalidRole {
+                role: RoleKind::Recovery,
+                violation: RoleBuilderValidation::NotYetValid(
+                    NotYetValidReason::RoleMustHaveAtLeastOneFactor
+                )
+            })
+        ));
+    }
+
+    #[test]
+    fn test_from_matrix_with_invalid_role_is_err() {
         let mut matrix = MatrixOfFactorInstances::sample();
         matrix.primary_role =
-            PrimaryRoleWithFactorInstances::with_factors(0, [], [FactorInstance::sample_other()]);
+            PrimaryRoleWithFactorInstances::try_with_factors(2, [securified(0).into()], [])
+                .unwrap();
+
+        assert_eq!(
+            SUT::try_from((matrix, RoleKind::Primary)),
+            Err(GeneralRoleWithFactorInstancesError::InvalidRole {
+                role: RoleKind::Primary,
+                violation: RoleBuilderValidation::NotYetValid(
+                    NotYetValidReason::ThresholdHigherThanThresholdFactorsLen
+                )
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_matrix_containing_physical_badge() {
+        let mut matrix = MatrixOfFactorInstances::sample();
+        matrix.primary_role = PrimaryRoleWithFactorInstances::try_with_factors(
+            0,
+            [],
+            [FactorInstance::sample_other()],
+        )
+        .unwrap();
 
         assert_eq!(
             SUT::try_from((matrix, RoleKind::Primary)),
-            Err(CommonError::BadgeIsNotVirtualHierarchicalDeterministic)
+            Err(
+                GeneralRoleWithFactorInstancesError::NotHierarchicalDeterministic {
+                    role: RoleKind::Primary,
+                    factor_instance: FactorInstance::sample_other(),
+                }
+            )
         );
     }
 }
@@ -31,15 +31,44 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn primary_role_non_securified_threshold_instances_is_err() {
-        let _ = SUT::with_factors(
-                1,
-                [
-                    HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_10_unsecurified_at_index(0).into()
-                ],
-                []
-            );
+        let instance = HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_10_unsecurified_at_index(0);
+        let err = SUT::try_with_factors(1, [instance.clone().into()], []).unwrap_err();
+        assert_eq!(err.role, RoleKind::Primary);
+        assert_eq!(err.list, FactorListKind::Threshold);
+        assert_ne!(err.key_space, KeySpace::Securified);
+        assert_eq!(err.factor_instance, instance);
+    }
+
+    #[test]
+    fn primary_role_non_securified_override_instances_is_err() {
+        let res = SUT::try_with_factors(
+            0,
+            [],
+            [
+                HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_0_securified_at_index(0).into(),
+                HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_10_unsecurified_at_index(0).into(),
+            ],
+        );
+        assert!(matches!(
+            res,
+            Err(FactorNotSecurified {
+                list: FactorListKind::Override,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn primary_role_securified_instances_is_ok() {
+        let res = SUT::try_with_factors(
+            1,
+            [
+                HierarchicalDeterministicFactorInstance::sample_mainnet_account_device_factor_fs_0_securified_at_index(0).into(),
+            ],
+            [],
+        );
+        assert!(res.is_ok());
     }
 
     #[test]
@@ -3,6 +3,14 @@ use crate::prelude::*;
 pub(crate) type RoleWithFactorInstances<const R: u8> =
     AbstractBuiltRoleWithFactor<R, FactorInstance>;
 
+impl<const R: u8> IsValidatedOnDeserialization for RoleWithFactorInstances<R> {
+    type Error = FactorNotSecurified;
+
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        self.validate_securified()
+    }
+}
+
 impl<const R: u8> RoleWithFactorSources<R> {
     fn from<const F: u8>(other: &RoleWithFactorSources<F>) -> Self {
         Self::with_factors(
@@ -26,9 +34,9 @@ impl MatrixOfFactorSources {
 
 impl<const R: u8> RoleWithFactorInstances<R> {
     pub(crate) fn fulfilling_role_of_factor_sources_with_factor_instances(
-        consuming_instances: &IndexMap<FactorSourceIDFromHash, FactorInstances>,
+        consuming_instances: &IndexMap<FactorSourceID, IndexSet<FactorInstance>>,
         matrix_of_factor_sources: &MatrixOfFactorSources,
-    ) -> Result<Self, CommonError> {
+    ) -> Result<Self, MatrixFulfillmentError> {
         let role_kind = RoleKind::from_u8(R).unwrap();
 
         let role_of_sources = matrix_of_factor_sources.get_role::<R>();
@@ -50,27 +58,23 @@ impl<const R: u8> RoleWithFactorInstances<R> {
             )?;
 
         let role_with_instances =
-            Self::with_factors(threshold, threshold_factors, override_factors);
+            Self::try_with_factors(threshold, threshold_factors, override_factors)?;
 
         assert_eq!(role_with_instances.role(), role_kind);
         Ok(role_with_instances)
     }
 
     fn try_filling_factor_list_of_role_of_factor_sources_with_factor_instances(
-        instances: &IndexMap<FactorSourceIDFromHash, FactorInstances>,
+        instances: &IndexMap<FactorSourceID, IndexSet<FactorInstance>>,
         from: &[FactorSource],
     ) -> Result<Vec<FactorInstance>, CommonError> {
         from.iter()
             .map(|f| {
-                if let Some(existing) = instances.get(&f.id_from_hash()) {
-                    let hd_instance = existing
-                        .first()
-                        .ok_or(CommonError::MissingFactorMappingInstancesIntoRole)?;
-                    let instance = FactorInstance::from(hd_instance);
-                    Ok(instance)
-                } else {
-                    Err(CommonError::MissingFactorMappingInstancesIntoRole)
-                }
+                instances
+                    .get(&f.id())
+                    .and_then(|existing| existing.first())
+                    .cloned()
+                    .ok_or(CommonError::MissingFactorMappingInstancesIntoRole)
             })
             .collect::<Result<Vec<FactorInstance>, CommonError>>()
     }
@@ -1,3 +1,14 @@
 use crate::prelude::*;
 
 pub type RoleWithFactorSourceIds<const R: u8> = AbstractBuiltRoleWithFactor<R, FactorSourceID>;
+
+impl<const R: u8> IsValidatedOnDeserialization for RoleWithFactorSourceIds<R> {
+    type Error = MatrixBuilderValidation;
+
+    /// Validates the role in isolation using the same rules as `RoleBuilder`.
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        RoleBuilder::from_built(self.clone())
+            .validate()
+            .into_matrix_err(self.role())
+    }
+}
@@ -4,7 +4,8 @@ use crate::prelude::*;
 /// FactorSourceKind with some placeholder ID, to distinguish between two different
 /// FactorSourceIDs of some kind, e.g. `FactorSourceID::sample()` and `FactorSourceID::sample_other()`.
 /// but exactly which FactorSourceID values are not yet known, since this is a template.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct FactorSourceTemplate {
     /// The kind of FactorSource, e.g. Device, LedgerHQHardwareWallet, Password, etc.
     pub kind: FactorSourceKind,
@@ -20,6 +21,34 @@ pub(crate) type PrimaryRoleTemplate = RoleTemplate<{ ROLE_PRIMARY }>;
 pub(crate) type RecoveryRoleTemplate = RoleTemplate<{ ROLE_RECOVERY }>;
 pub(crate) type ConfirmationRoleTemplate = RoleTemplate<{ ROLE_CONFIRMATION }>;
 
+impl<const R: u8> RoleTemplate<R> {
+    /// Materializes this template by assigning each template slot its
+    /// placeholder FactorSourceID, used to validate the structure of the template.
+    pub(crate) fn with_placeholder_factor_source_ids(&self) -> RoleWithFactorSourceIds<R> {
+        let ids = |xs: &Vec<FactorSourceTemplate>| {
+            xs.iter()
+                .map(FactorSourceTemplate::placeholder_factor_source_id)
+                .collect_vec()
+        };
+        RoleWithFactorSourceIds::with_factors(
+            self.get_threshold(),
+            ids(self.get_threshold_factors()),
+            ids(self.get_override_factors()),
+        )
+    }
+}
+
+impl<const R: u8> IsValidatedOnDeserialization for RoleTemplate<R> {
+    type Error = MatrixBuilderValidation;
+
+    /// Validates the role with a distinct placeholder FactorSourceID per
+    /// template slot, using the same rules as `RoleBuilder`.
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        self.with_placeholder_factor_source_ids()
+            .validate_deserialized()
+    }
+}
+
 impl PrimaryRoleTemplate {
     pub(crate) fn new(threshold_factors: impl IntoIterator<Item = FactorSourceTemplate>) -> Self {
         let threshold_factors = threshold_factors.into_iter().collect_vec();
@@ -44,6 +73,12 @@ impl FactorSourceTemplate {
         Self { kind, id }
     }
 
+    /// A FactorSourceID of the kind of this template, distinct for each
+    /// distinct template, not belonging to any real factor source.
+    pub(crate) fn placeholder_factor_source_id(&self) -> FactorSourceID {
+        FactorSourceIDFromHash::new(self.kind, sargon::Exactly32Bytes::from([self.id; 32])).into()
+    }
+
     pub fn device() -> Self {
         Self::new(FactorSourceKind::Device, 0)
     }
@@ -2,6 +2,27 @@ use crate::prelude::*;
 
 pub(crate) type RoleWithFactorSources<const R: u8> = AbstractBuiltRoleWithFactor<R, FactorSource>;
 
+impl<const R: u8> RoleWithFactorSources<R> {
+    /// Returns a role with the ids of the factor sources of this role.
+    pub(crate) fn factor_source_ids(&self) -> RoleWithFactorSourceIds<R> {
+        let ids = |xs: &Vec<FactorSource>| xs.iter().map(|f| f.id()).collect_vec();
+        RoleWithFactorSourceIds::with_factors(
+            self.get_threshold(),
+            ids(self.get_threshold_factors()),
+            ids(self.get_override_factors()),
+        )
+    }
+}
+
+impl<const R: u8> IsValidatedOnDeserialization for RoleWithFactorSources<R> {
+    type Error = MatrixBuilderValidation;
+
+    /// Validates the role of the ids of the factor sources.
+    fn validate_deserialized(&self) -> Result<(), Self::Error> {
+        self.factor_source_ids().validate_deserialized()
+    }
+}
+
 impl<const R: u8> RoleWithFactorSources<R> {
     pub fn new(
         role_with_factor_source_ids: RoleWithFactorSourceIds<R>,
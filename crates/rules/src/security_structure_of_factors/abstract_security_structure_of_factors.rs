@@ -2,6 +2,7 @@ use crate::prelude::*;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[serde(bound(deserialize = "AbstractMatrixBuilt<F>: Deserialize<'de>"))]
 pub struct AbstractSecurityStructure<F> {
     /// Metadata of this Security Structure, such as globally unique and
     /// stable identifier, creation date and user chosen label (name).
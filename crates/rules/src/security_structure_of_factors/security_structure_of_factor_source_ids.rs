@@ -2,6 +2,59 @@ use crate::prelude::*;
 
 pub type SecurityStructureOfFactorSourceIds = AbstractSecurityStructure<FactorSourceID>;
 
+/// The serialized form of a `SecurityStructureOfFactorSourceIds`, deserialized
+/// without validating its matrix of factors.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UnvalidatedSecurityStructureOfFactorSourceIds {
+    metadata: sargon::SecurityStructureMetadata,
+    matrix_of_factors: UnvalidatedMatrix<FactorSourceID>,
+}
+
+impl SecurityStructureOfFactorSourceIds {
+    /// Returns a `MatrixBuilder` seeded with the matrix of this shield, used to
+    /// edit it, the built matrix can then be passed to `edited`.
+    pub fn matrix_builder(&self) -> MatrixBuilder {
+        MatrixBuilder::with_matrix(self.matrix_of_factors.clone())
+    }
+
+    /// Returns an updated version of this shield with `display_name` and
+    /// `matrix_of_factors`, keeping its id and creation date, but bumping
+    /// its last updated date.
+    pub fn edited(
+        &self,
+        display_name: DisplayName,
+        matrix_of_factors: MatrixOfFactorSourceIds,
+    ) -> Self {
+        let mut metadata = self.metadata.clone();
+        metadata.display_name = display_name;
+        metadata.last_updated_on = sargon::Timestamp::now_utc();
+        Self::with_metadata(metadata, matrix_of_factors)
+    }
+}
+
+impl SecurityStructureOfFactorSourceIds {
+    /// Deserializes a SecurityStructureOfFactorSourceIds from `json` and validates
+    /// its matrix of factors, like `serde_json::from_str` does, but returning the
+    /// validation error typed.
+    pub fn from_json_str(json: impl AsRef<str>) -> Result<Self, MatrixDeserializationError> {
+        let unvalidated =
+            serde_json::from_str::<UnvalidatedSecurityStructureOfFactorSourceIds>(json.as_ref())?;
+        let matrix_of_factors = MatrixOfFactorSourceIds::validated(unvalidated.matrix_of_factors)?;
+        Ok(Self::with_metadata(unvalidated.metadata, matrix_of_factors))
+    }
+
+    /// Serializes this SecurityStructureOfFactorSourceIds to JSON, which can
+    /// be imported using `from_json_str`.
+    ///
+    /// Cannot fail, since `serde_json` only fails to serialize maps with non
+    /// string keys and types whose `Serialize` impl fails, and a shield
+    /// contains neither.
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string(self).expect("Should always be able to serialize a shield")
+    }
+}
+
 impl HasSampleValues for SecurityStructureOfFactorSourceIds {
     fn sample() -> Self {
         let metadata = sargon::SecurityStructureMetadata::sample();
@@ -31,6 +84,61 @@ mod tests {
         assert_ne!(SUT::sample(), SUT::sample_other());
     }
 
+    #[test]
+    fn from_json_str_valid() {
+        let json = serde_json::to_string(&SUT::sample_other()).unwrap();
+        assert_eq!(SUT::from_json_str(json), Ok(SUT::sample_other()));
+    }
+
+    #[test]
+    fn to_json_string_roundtrip() {
+        let sut = SUT::sample();
+        assert_eq!(SUT::from_json_str(sut.to_json_string()), Ok(sut));
+    }
+
+    #[test]
+    fn from_json_str_invalid_matrix() {
+        let mut json = serde_json::to_value(SUT::sample()).unwrap();
+        json["matrixOfFactors"]["recoveryRole"]["overrideFactors"] = serde_json::json!([]);
+        assert_eq!(
+            SUT::from_json_str(json.to_string()),
+            Err(MatrixDeserializationError::InvalidMatrix(
+                MatrixBuilderValidation::RoleInIsolation {
+                    role: RoleKind::Recovery,
+                    violation: RoleBuilderValidation::NotYetValid(
+                        NotYetValidReason::RoleMustHaveAtLeastOneFactor
+                    )
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn serde_json_from_str_invalid_matrix_is_err() {
+        let mut json = serde_json::to_value(SUT::sample()).unwrap();
+        json["matrixOfFactors"]["recoveryRole"]["overrideFactors"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<SUT>(json).is_err());
+    }
+
+    #[test]
+    fn edited_keeps_id_and_bumps_last_updated_on() {
+        let sut = SUT::sample();
+        let mut builder = sut.matrix_builder();
+        builder
+            .add_factor_source_to_confirmation_override(FactorSourceID::sample_arculus())
+            .unwrap();
+        let matrix = builder.build().unwrap();
+
+        let edited = sut.edited(DisplayName::new("Edited").unwrap(), matrix.clone());
+
+        assert_eq!(edited.metadata.id, sut.metadata.id);
+        assert_eq!(edited.metadata.created_on, sut.metadata.created_on);
+        assert!(edited.metadata.last_updated_on > sut.metadata.last_updated_on);
+        assert_eq!(edited.metadata.display_name.value, "Edited");
+        assert_eq!(edited.matrix_of_factors, matrix);
+        assert_ne!(edited, sut);
+    }
+
     #[test]
     fn assert_json_sample() {
         let sut = SUT::sample();